@@ -0,0 +1,272 @@
+use crate::model::{strip_color_codes, Direction, Room, SectorTable, Vnum};
+use crate::parser::{ParsedArea, RoomsByVnum};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// Something that's different about the rooms in a newer version of an area. Names have their
+/// color codes taken out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Change {
+    AddedRoom {
+        room: Vnum,
+        name: String,
+    },
+    RemovedRoom {
+        room: Vnum,
+        name: String,
+    },
+    RenamedRoom {
+        room: Vnum,
+        old: String,
+        new: String,
+    },
+    ChangedSector {
+        room: Vnum,
+        old: String,
+        new: String,
+    },
+    AddedExit {
+        room: Vnum,
+        direction: Direction,
+        to: Vnum,
+    },
+    RemovedExit {
+        room: Vnum,
+        direction: Direction,
+        to: Vnum,
+    },
+    RetargetedExit {
+        room: Vnum,
+        direction: Direction,
+        old: Vnum,
+        new: Vnum,
+    },
+}
+
+impl Change {
+    pub fn room(&self) -> Vnum {
+        match *self {
+            Change::AddedRoom { room, .. }
+            | Change::RemovedRoom { room, .. }
+            | Change::RenamedRoom { room, .. }
+            | Change::ChangedSector { room, .. }
+            | Change::AddedExit { room, .. }
+            | Change::RemovedExit { room, .. }
+            | Change::RetargetedExit { room, .. } => room,
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::AddedRoom { room, name } => write!(f, "Room {room} \"{name}\" was added"),
+            Change::RemovedRoom { room, name } => write!(f, "Room {room} \"{name}\" was removed"),
+            Change::RenamedRoom { room, old, new } => {
+                write!(f, "Room {room} was renamed from \"{old}\" to \"{new}\"")
+            }
+            Change::ChangedSector { room, old, new } => {
+                write!(f, "Room {room}'s sector changed from {old} to {new}")
+            }
+            Change::AddedExit {
+                room,
+                direction,
+                to,
+            } => write!(f, "Room {room} has a new {direction:?} exit to {to}"),
+            Change::RemovedExit {
+                room,
+                direction,
+                to,
+            } => write!(f, "Room {room}'s {direction:?} exit to {to} was removed"),
+            Change::RetargetedExit {
+                room,
+                direction,
+                old,
+                new,
+            } => write!(
+                f,
+                "Room {room}'s {direction:?} exit leads to {new} instead of {old}"
+            ),
+        }
+    }
+}
+
+/// Compares the rooms of two versions of an area. The exits of rooms that were added or removed
+/// aren't listed separately.
+pub fn diff_areas(old: &ParsedArea, new: &ParsedArea, sectors: &SectorTable) -> Vec<Change> {
+    diff_rooms(
+        &old.all_rooms,
+        &new.all_rooms,
+        sectors,
+        |room| strip_color_codes(&room.name, old.color_codes(room.vnum)),
+        |room| strip_color_codes(&room.name, new.color_codes(room.vnum)),
+    )
+}
+
+/// Compares the rooms, naming them with `old_name` and `new_name`
+fn diff_rooms(
+    old: &RoomsByVnum,
+    new: &RoomsByVnum,
+    sectors: &SectorTable,
+    old_name: impl Fn(&Room) -> String,
+    new_name: impl Fn(&Room) -> String,
+) -> Vec<Change> {
+    let mut changes = vec![];
+    for (room, _) in old.values() {
+        if !new.contains_key(&room.vnum) {
+            changes.push(Change::RemovedRoom {
+                room: room.vnum,
+                name: old_name(room),
+            });
+        }
+    }
+
+    for (room, _) in new.values() {
+        let vnum = room.vnum;
+        let before = match old.get(&vnum) {
+            Some((before, _)) => before,
+            None => {
+                changes.push(Change::AddedRoom {
+                    room: vnum,
+                    name: new_name(room),
+                });
+                continue;
+            }
+        };
+
+        let (old, new) = (old_name(before), new_name(room));
+        if old != new {
+            changes.push(Change::RenamedRoom {
+                room: vnum,
+                old,
+                new,
+            });
+        }
+        if before.sector != room.sector {
+            changes.push(Change::ChangedSector {
+                room: vnum,
+                old: sectors.style(before.sector).name.clone(),
+                new: sectors.style(room.sector).name.clone(),
+            });
+        }
+
+        for (&direction, exit) in &before.exits {
+            match room.exits.get(&direction) {
+                None => changes.push(Change::RemovedExit {
+                    room: vnum,
+                    direction,
+                    to: exit.destination,
+                }),
+                Some(now) if now.destination != exit.destination => {
+                    changes.push(Change::RetargetedExit {
+                        room: vnum,
+                        direction,
+                        old: exit.destination,
+                        new: now.destination,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for (&direction, exit) in &room.exits {
+            if !before.exits.contains_key(&direction) {
+                changes.push(Change::AddedExit {
+                    room: vnum,
+                    direction,
+                    to: exit.destination,
+                });
+            }
+        }
+    }
+
+    changes.sort_by_key(|change| {
+        let direction = match change {
+            Change::AddedExit { direction, .. }
+            | Change::RemovedExit { direction, .. }
+            | Change::RetargetedExit { direction, .. } => Some(*direction),
+            _ => None,
+        };
+        (change.room(), direction)
+    });
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff_rooms, Change};
+    use crate::model::{Direction, Room, Sector, SectorTable, Vnum};
+    use crate::parser::rooms_by_vnum;
+    use std::rc::Rc;
+
+    fn room(vnum: Vnum, name: &str, sector: Sector, exits: &[(Direction, Vnum)]) -> Room {
+        Room {
+            name: name.to_string(),
+            sector,
+            ..Room::test(vnum, exits)
+        }
+    }
+
+    #[test]
+    fn finds_changed_rooms_and_exits() {
+        use Direction::*;
+        use Sector::*;
+        let old = rooms_by_vnum(
+            &[
+                room(1, "Gate", City, &[(North, 2), (East, 3)]),
+                room(2, "Square", City, &[(South, 1)]),
+                room(3, "Shed", Inside, &[(West, 1)]),
+            ]
+            .map(Rc::new),
+        );
+        let new = rooms_by_vnum(
+            &[
+                room(1, "Gate", City, &[(North, 2), (West, 4)]),
+                room(2, "Market Square", Field, &[(South, 1), (Up, 4)]),
+                room(4, "Tower", Inside, &[(East, 1)]),
+            ]
+            .map(Rc::new),
+        );
+
+        let name = |room: &Room| room.name.clone();
+        let changes = diff_rooms(&old, &new, &SectorTable::default(), name, name);
+        assert_eq!(
+            changes,
+            vec![
+                Change::RemovedExit {
+                    room: 1,
+                    direction: East,
+                    to: 3
+                },
+                Change::AddedExit {
+                    room: 1,
+                    direction: West,
+                    to: 4
+                },
+                Change::RenamedRoom {
+                    room: 2,
+                    old: "Square".to_string(),
+                    new: "Market Square".to_string()
+                },
+                Change::ChangedSector {
+                    room: 2,
+                    old: "City".to_string(),
+                    new: "Field".to_string()
+                },
+                Change::AddedExit {
+                    room: 2,
+                    direction: Up,
+                    to: 4
+                },
+                Change::RemovedRoom {
+                    room: 3,
+                    name: "Shed".to_string()
+                },
+                Change::AddedRoom {
+                    room: 4,
+                    name: "Tower".to_string()
+                },
+            ]
+        );
+    }
+}
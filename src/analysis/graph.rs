@@ -0,0 +1,311 @@
+use crate::model::Vnum;
+use crate::parser::RoomsByVnum;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// How the rooms hang together, for judging how much of a maze an area is
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphReport {
+    pub rooms: usize,
+    pub average_exits: f32,
+    /// Groups of rooms that can all get to each other, biggest first
+    pub components: Vec<Vec<Vnum>>,
+    /// Rooms that would cut the rest in two if they weren't there
+    pub articulation_rooms: Vec<Vnum>,
+    /// Connections that would cut the rooms in two if they weren't there
+    pub bridges: Vec<(Vnum, Vnum)>,
+    pub diameter: Option<Diameter>,
+    /// Corridors that lead nowhere, each from its dead end back to where it branches off
+    pub dead_ends: Vec<Vec<Vnum>>,
+}
+
+/// The two rooms furthest apart, of those that are connected at all
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct Diameter {
+    pub steps: u32,
+    pub from: Vnum,
+    pub to: Vnum,
+}
+
+/// Exits between rooms, by index into the sorted vnums
+struct Graph {
+    vnums: Vec<Vnum>,
+    /// Exits as they lead
+    directed: Vec<Vec<usize>>,
+    /// Rooms an exit leads to or from, each once
+    undirected: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    fn new(rooms: &RoomsByVnum) -> Self {
+        let mut vnums: Vec<_> = rooms.keys().copied().collect();
+        vnums.sort_unstable();
+        let index = |vnum: Vnum| vnums.binary_search(&vnum).ok();
+
+        let mut directed = vec![vec![]; vnums.len()];
+        let mut undirected = vec![vec![]; vnums.len()];
+        for (from, &vnum) in vnums.iter().enumerate() {
+            let mut exits: Vec<_> = rooms[&vnum].0.exits.iter().collect();
+            exits.sort_by_key(|(&direction, _)| direction);
+            for (_, exit) in exits {
+                if let Some(to) = index(exit.destination).filter(|&to| to != from) {
+                    directed[from].push(to);
+                    undirected[from].push(to);
+                    undirected[to].push(from);
+                }
+            }
+        }
+        for neighbours in &mut undirected {
+            neighbours.sort_unstable();
+            neighbours.dedup();
+        }
+        Graph {
+            vnums,
+            directed,
+            undirected,
+        }
+    }
+}
+
+pub fn analyze_graph(rooms: &RoomsByVnum) -> GraphReport {
+    let graph = Graph::new(rooms);
+    let exits: usize = rooms.values().map(|(room, _)| room.exits.len()).sum();
+    let (articulation_rooms, bridges) = chokepoints(&graph);
+    GraphReport {
+        rooms: rooms.len(),
+        average_exits: if rooms.is_empty() {
+            0f32
+        } else {
+            exits as f32 / rooms.len() as f32
+        },
+        components: strong_components(&graph),
+        articulation_rooms,
+        bridges,
+        diameter: diameter(&graph),
+        dead_ends: dead_ends(&graph),
+    }
+}
+
+/// Tarjan's algorithm, with the recursion turned into a stack so big worlds don't overflow it
+fn strong_components(graph: &Graph) -> Vec<Vec<Vnum>> {
+    let count = graph.vnums.len();
+    let mut order = vec![usize::MAX; count];
+    let mut low = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut stack = vec![];
+    let mut next_order = 0;
+    let mut components = vec![];
+
+    for start in 0..count {
+        if order[start] != usize::MAX {
+            continue;
+        }
+        let mut calls = vec![(start, 0)];
+        order[start] = next_order;
+        low[start] = next_order;
+        next_order += 1;
+        stack.push(start);
+        on_stack[start] = true;
+
+        while let Some(&mut (room, ref mut next_exit)) = calls.last_mut() {
+            if let Some(&to) = graph.directed[room].get(*next_exit) {
+                *next_exit += 1;
+                if order[to] == usize::MAX {
+                    order[to] = next_order;
+                    low[to] = next_order;
+                    next_order += 1;
+                    stack.push(to);
+                    on_stack[to] = true;
+                    calls.push((to, 0));
+                } else if on_stack[to] {
+                    low[room] = low[room].min(order[to]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(caller, _)) = calls.last() {
+                low[caller] = low[caller].min(low[room]);
+            }
+            if low[room] == order[room] {
+                let mut component = vec![];
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(graph.vnums[member]);
+                    if member == room {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    components
+}
+
+/// Articulation rooms and bridges, ignoring which way exits lead
+fn chokepoints(graph: &Graph) -> (Vec<Vnum>, Vec<(Vnum, Vnum)>) {
+    let count = graph.vnums.len();
+    let mut order = vec![usize::MAX; count];
+    let mut low = vec![0; count];
+    let mut parent = vec![usize::MAX; count];
+    let mut is_articulation = vec![false; count];
+    let mut bridges = vec![];
+    let mut next_order = 0;
+
+    for root in 0..count {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        order[root] = next_order;
+        low[root] = next_order;
+        next_order += 1;
+        let mut root_children = 0;
+        let mut calls = vec![(root, 0)];
+
+        while let Some(&mut (room, ref mut next_neighbour)) = calls.last_mut() {
+            if let Some(&to) = graph.undirected[room].get(*next_neighbour) {
+                *next_neighbour += 1;
+                if order[to] == usize::MAX {
+                    parent[to] = room;
+                    order[to] = next_order;
+                    low[to] = next_order;
+                    next_order += 1;
+                    calls.push((to, 0));
+                } else if to != parent[room] {
+                    low[room] = low[room].min(order[to]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            let above = parent[room];
+            if above == usize::MAX {
+                continue;
+            }
+            low[above] = low[above].min(low[room]);
+            if low[room] > order[above] {
+                let (a, b) = (graph.vnums[above], graph.vnums[room]);
+                bridges.push((a.min(b), a.max(b)));
+            }
+            if above == root {
+                root_children += 1;
+            } else if low[room] >= order[above] {
+                is_articulation[above] = true;
+            }
+        }
+        if root_children > 1 {
+            is_articulation[root] = true;
+        }
+    }
+
+    let articulation_rooms = (0..count)
+        .filter(|&idx| is_articulation[idx])
+        .map(|idx| graph.vnums[idx])
+        .collect();
+    bridges.sort_unstable();
+    (articulation_rooms, bridges)
+}
+
+/// The longest of the shortest walks between any two rooms, ignoring which way exits lead. Takes a
+/// walk from every room, so it's the slow part on big worlds.
+fn diameter(graph: &Graph) -> Option<Diameter> {
+    let mut longest: Option<Diameter> = None;
+    let mut steps = vec![u32::MAX; graph.vnums.len()];
+    for from in 0..graph.vnums.len() {
+        steps.fill(u32::MAX);
+        steps[from] = 0;
+        let mut queue = VecDeque::from([from]);
+        let mut furthest = from;
+        while let Some(room) = queue.pop_front() {
+            furthest = room;
+            for &to in &graph.undirected[room] {
+                if steps[to] == u32::MAX {
+                    steps[to] = steps[room] + 1;
+                    queue.push_back(to);
+                }
+            }
+        }
+        let further = match longest {
+            Some(longest) => steps[furthest] > longest.steps,
+            None => true,
+        };
+        if further {
+            longest = Some(Diameter {
+                steps: steps[furthest],
+                from: graph.vnums[from],
+                to: graph.vnums[furthest],
+            });
+        }
+    }
+    longest
+}
+
+/// Follows each room with a single neighbour back along the corridor it's at the end of, until
+/// the corridor branches
+fn dead_ends(graph: &Graph) -> Vec<Vec<Vnum>> {
+    let degree = |idx: usize| graph.undirected[idx].len();
+    let mut chains = vec![];
+    for start in (0..graph.vnums.len()).filter(|&idx| degree(idx) == 1) {
+        let mut chain = vec![graph.vnums[start]];
+        let (mut previous, mut room) = (start, graph.undirected[start][0]);
+        while degree(room) == 2 && room != start {
+            chain.push(graph.vnums[room]);
+            let next = graph.undirected[room]
+                .iter()
+                .copied()
+                .find(|&next| next != previous)
+                .unwrap();
+            previous = room;
+            room = next;
+        }
+        chains.push(chain);
+    }
+    chains
+}
+
+#[cfg(test)]
+mod test {
+    use super::{analyze_graph, Diameter};
+    use crate::model::{Direction, Room};
+    use crate::parser::rooms_by_vnum;
+    use std::rc::Rc;
+
+    #[test]
+    fn measures_the_room_graph() {
+        use Direction::*;
+        // A loop of 1-4, with a corridor of 5 and 6 off 4, and a one-way drop from 6 into 7
+        let rooms = rooms_by_vnum(
+            &[
+                Room::test(1, &[(East, 2), (North, 4)]),
+                Room::test(2, &[(West, 1), (North, 3)]),
+                Room::test(3, &[(South, 2), (West, 4)]),
+                Room::test(4, &[(South, 1), (East, 3), (North, 5)]),
+                Room::test(5, &[(South, 4), (North, 6)]),
+                Room::test(6, &[(South, 5), (Down, 7), (Up, 9000)]),
+                Room::test(7, &[]),
+            ]
+            .map(Rc::new),
+        );
+
+        let report = analyze_graph(&rooms);
+        assert_eq!(report.rooms, 7);
+        assert_eq!(report.average_exits, 2f32);
+        assert_eq!(report.components, vec![vec![1, 2, 3, 4, 5, 6], vec![7]]);
+        assert_eq!(report.articulation_rooms, vec![4, 5, 6]);
+        assert_eq!(report.bridges, vec![(4, 5), (5, 6), (6, 7)]);
+        assert_eq!(
+            report.diameter,
+            Some(Diameter {
+                steps: 5,
+                from: 2,
+                to: 7
+            })
+        );
+        assert_eq!(report.dead_ends, vec![vec![7, 6, 5]]);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::model::{Direction, Door, Vnum};
+use crate::parser::{
+    Collision, Connection, Diagnostic, ParsedArea, RoomsByVnum, StairMismatch, StairProblem,
+};
+use fnv::FnvHashSet;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintKind {
+    OneWayExit,
+    MissingRoom,
+    NoExits,
+    Unreachable,
+    NonOppositeReverse,
+    OneSidedDoor,
+    OverlappingRooms,
+    MisplacedStairs,
+}
+
+impl LintKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::OneWayExit => "one-way-exit",
+            LintKind::MissingRoom => "missing-room",
+            LintKind::NoExits => "no-exits",
+            LintKind::Unreachable => "unreachable",
+            LintKind::NonOppositeReverse => "non-opposite-reverse",
+            LintKind::OneSidedDoor => "one-sided-door",
+            LintKind::OverlappingRooms => "overlapping-rooms",
+            LintKind::MisplacedStairs => "misplaced-stairs",
+        }
+    }
+}
+
+/// Something about an area that loads fine, but probably isn't what the builder meant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub room: Vnum,
+    pub direction: Option<Direction>,
+    pub message: String,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind.name(), self.message)
+    }
+}
+
+/// Lints every room that was loaded. Exits to vnums outside the areas' vnum ranges lead to other
+/// areas on purpose, so only those inside are reported as missing rooms.
+pub fn lint_area(area: &ParsedArea) -> Vec<Lint> {
+    let ranges: Vec<_> = (0..area.areas.len())
+        .filter_map(|idx| area.vnum_range(idx))
+        .collect();
+    lint(
+        &area.all_rooms,
+        &area.connections,
+        &area.collisions,
+        &area.stacking.mismatches,
+        &ranges,
+    )
+}
+
+fn lint(
+    rooms: &RoomsByVnum,
+    connections: &[Connection],
+    collisions: &[Collision],
+    stairs: &[StairMismatch],
+    ranges: &[(Vnum, Vnum)],
+) -> Vec<Lint> {
+    let mut lints = vec![];
+    let exit = |vnum: Vnum, direction: Direction| {
+        rooms
+            .get(&vnum)
+            .and_then(|(room, _)| room.exits.get(&direction))
+    };
+
+    for connection in connections {
+        match connection {
+            Connection::OneWay { from, to, .. } => lints.push(Lint {
+                kind: LintKind::OneWayExit,
+                room: from.in_room,
+                direction: Some(from.direction),
+                message: format!(
+                    "Room {}'s {:?} exit to {} has no way back",
+                    from.in_room, from.direction, to.in_room
+                ),
+            }),
+            Connection::External { from, to, .. } => {
+                if ranges.iter().any(|&(low, high)| low <= *to && *to <= high) {
+                    lints.push(Lint {
+                        kind: LintKind::MissingRoom,
+                        room: from.in_room,
+                        direction: Some(from.direction),
+                        message: format!(
+                            "Room {}'s {:?} exit leads to room {}, which doesn't exist",
+                            from.in_room, from.direction, to
+                        ),
+                    });
+                }
+            }
+            Connection::TwoWay { from, to, .. } => {
+                if to.direction != from.direction.opposite() {
+                    lints.push(Lint {
+                        kind: LintKind::NonOppositeReverse,
+                        room: from.in_room,
+                        direction: Some(from.direction),
+                        message: format!(
+                            "Room {}'s {:?} exit to {} comes back {:?}, not {:?}",
+                            from.in_room,
+                            from.direction,
+                            to.in_room,
+                            to.direction,
+                            from.direction.opposite()
+                        ),
+                    });
+                }
+
+                // The connection merges both sides' doors, so compare the exits themselves
+                let (from_door, to_door) = match (
+                    exit(from.in_room, from.direction),
+                    exit(to.in_room, to.direction),
+                ) {
+                    (Some(from_exit), Some(to_exit)) => (from_exit.door, to_exit.door),
+                    _ => continue,
+                };
+                let differs = from_door.is_door != to_door.is_door
+                    || from_door.pickproof != to_door.pickproof
+                    || from_door.secret != to_door.secret;
+                if differs {
+                    lints.push(Lint {
+                        kind: LintKind::OneSidedDoor,
+                        room: from.in_room,
+                        direction: Some(from.direction),
+                        message: format!(
+                            "Room {}'s {:?} exit has {}, but room {}'s {:?} exit back has {}",
+                            from.in_room,
+                            from.direction,
+                            describe_door(from_door),
+                            to.in_room,
+                            to.direction,
+                            describe_door(to_door)
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut led_to = FnvHashSet::default();
+    for (room, _) in rooms.values() {
+        for exit in room.exits.values() {
+            if exit.destination != room.vnum {
+                led_to.insert(exit.destination);
+            }
+        }
+    }
+    for (room, _) in rooms.values() {
+        if room.exits.is_empty() {
+            lints.push(Lint {
+                kind: LintKind::NoExits,
+                room: room.vnum,
+                direction: None,
+                message: format!("Room {} has no exits", room.vnum),
+            });
+        }
+        if !led_to.contains(&room.vnum) {
+            lints.push(Lint {
+                kind: LintKind::Unreachable,
+                room: room.vnum,
+                direction: None,
+                message: format!("No exit leads to room {}", room.vnum),
+            });
+        }
+    }
+
+    // Both rooms get the lint, since either could be the one drawn on top
+    for collision in collisions {
+        for (room, other) in [
+            (collision.room, collision.other),
+            (collision.other, collision.room),
+        ] {
+            lints.push(Lint {
+                kind: LintKind::OverlappingRooms,
+                room,
+                direction: None,
+                message: format!("Room {room} is drawn on the same spot as room {other}"),
+            });
+        }
+    }
+
+    for mismatch in stairs {
+        let StairMismatch {
+            room,
+            direction,
+            to,
+            problem,
+        } = *mismatch;
+        let message = match problem {
+            StairProblem::Level { expected, found } => format!(
+                "Room {room}'s {direction:?} exit to {to} goes {}, but the other stairs put room \
+                 {to} {}",
+                describe_rise(expected),
+                describe_rise(found)
+            ),
+            StairProblem::Misaligned => format!(
+                "Room {room}'s {direction:?} exit to {to} doesn't line up with the other stairs \
+                 between those floors"
+            ),
+        };
+        lints.push(Lint {
+            kind: LintKind::MisplacedStairs,
+            room,
+            direction: Some(direction),
+            message,
+        });
+    }
+
+    lints.sort_by_key(|lint| (lint.room, lint.kind, lint.direction));
+    lints
+}
+
+fn describe_door(door: Door) -> &'static str {
+    match (door.is_door, door.pickproof, door.secret) {
+        (false, _, _) => "no door",
+        (true, false, false) => "a door",
+        (true, true, false) => "a pickproof door",
+        (true, false, true) => "a secret door",
+        (true, true, true) => "a secret pickproof door",
+    }
+}
+
+fn describe_rise(floors: i32) -> String {
+    match floors {
+        0 => "on the same floor".to_string(),
+        1 => "1 floor up".to_string(),
+        -1 => "1 floor down".to_string(),
+        up if up > 0 => format!("{up} floors up"),
+        down => format!("{} floors down", -down),
+    }
+}
+
+/// Everything found about the loaded areas, as one JSON document for CI to pick apart
+pub fn report_json(diagnostics: &[Diagnostic], lints: &[Lint]) -> String {
+    #[derive(Serialize)]
+    struct Report<'a> {
+        diagnostics: &'a [Diagnostic],
+        lints: &'a [Lint],
+    }
+    serde_json::to_string_pretty(&Report { diagnostics, lints }).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lint, LintKind};
+    use crate::model::{Direction, Door, Room};
+    use crate::parser::{find_connections, rooms_by_vnum, Collision, StairMismatch, StairProblem};
+    use std::rc::Rc;
+
+    #[test]
+    fn finds_each_kind_of_problem() {
+        use Direction::*;
+        let door = Door::from_locks(1);
+        let rooms = [
+            Room::test(1, &[(North, 2), (East, 3)]).with_door(North, door),
+            Room::test(2, &[(South, 1), (West, 4)]),
+            // Leaving the areas' vnum range is fine
+            Room::test(3, &[(Up, 9000)]),
+            Room::test(4, &[(North, 2), (Down, 50)]),
+            Room::test(5, &[]),
+        ]
+        .map(Rc::new);
+        let connections = find_connections(&rooms);
+        let by_vnum = rooms_by_vnum(&rooms);
+
+        let lints: Vec<_> = lint(
+            &by_vnum,
+            &connections,
+            &[Collision { room: 4, other: 1 }],
+            &[StairMismatch {
+                room: 3,
+                direction: Up,
+                to: 9000,
+                problem: StairProblem::Misaligned,
+            }],
+            &[(1, 100)],
+        )
+        .into_iter()
+        .map(|lint| (lint.room, lint.kind))
+        .collect();
+        assert_eq!(
+            lints,
+            vec![
+                (1, LintKind::OneWayExit),
+                (1, LintKind::OneSidedDoor),
+                (1, LintKind::OverlappingRooms),
+                (2, LintKind::NonOppositeReverse),
+                (3, LintKind::MisplacedStairs),
+                (4, LintKind::MissingRoom),
+                (4, LintKind::OverlappingRooms),
+                (5, LintKind::NoExits),
+                (5, LintKind::Unreachable),
+            ]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+mod diff;
+mod graph;
+mod lint;
+mod reach;
+mod route;
+mod vnums;
+
+pub use diff::{diff_areas, Change};
+pub use graph::{analyze_graph, GraphReport};
+pub use lint::{lint_area, report_json, Lint, LintKind};
+pub use reach::reachability;
+pub use route::{find_route, DoorPolicy, Route, Traveler};
+pub use vnums::vnum_report;
@@ -0,0 +1,120 @@
+use crate::analysis::Traveler;
+use crate::model::{SectorTable, Vnum};
+use crate::parser::RoomsByVnum;
+use fnv::{FnvHashMap, FnvHashSet};
+use serde::Serialize;
+
+/// Rooms that can't be got to from the entrances, and rooms that can be got to but never left
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Reachability {
+    pub unreachable: Vec<Vnum>,
+    /// Rooms from which there's no way back to an entrance, or out of the loaded areas
+    pub traps: Vec<Vnum>,
+}
+
+/// Finds where `traveler` can get to from the `entries`, following exits only the way they lead
+pub fn reachability(
+    rooms: &RoomsByVnum,
+    sectors: &SectorTable,
+    traveler: &Traveler,
+    entries: &[Vnum],
+) -> Reachability {
+    let mut forward: FnvHashMap<Vnum, Vec<Vnum>> = FnvHashMap::default();
+    let mut backward: FnvHashMap<Vnum, Vec<Vnum>> = FnvHashMap::default();
+    let mut ways_out = vec![];
+    for (here, _) in rooms.values() {
+        for exit in here.exits.values() {
+            match rooms.get(&exit.destination) {
+                Some((there, _)) => {
+                    if traveler.can_take(here, exit, there, sectors) {
+                        forward.entry(here.vnum).or_default().push(there.vnum);
+                        backward.entry(there.vnum).or_default().push(here.vnum);
+                    }
+                }
+                // Another area is as good a way out as any
+                None => {
+                    if traveler.can_pass(exit.door) {
+                        ways_out.push(here.vnum);
+                    }
+                }
+            }
+        }
+    }
+
+    let entries: Vec<_> = entries
+        .iter()
+        .copied()
+        .filter(|vnum| rooms.contains_key(vnum))
+        .collect();
+    let reached = flood(&entries, &forward);
+    ways_out.extend(&entries);
+    let can_leave = flood(&ways_out, &backward);
+
+    let mut unreachable: Vec<_> = rooms
+        .keys()
+        .copied()
+        .filter(|vnum| !reached.contains(vnum))
+        .collect();
+    let mut traps: Vec<_> = reached
+        .iter()
+        .copied()
+        .filter(|vnum| !can_leave.contains(vnum))
+        .collect();
+    unreachable.sort_unstable();
+    traps.sort_unstable();
+    Reachability { unreachable, traps }
+}
+
+fn flood(start: &[Vnum], edges: &FnvHashMap<Vnum, Vec<Vnum>>) -> FnvHashSet<Vnum> {
+    let mut seen: FnvHashSet<Vnum> = start.iter().copied().collect();
+    let mut to_visit = start.to_vec();
+    while let Some(vnum) = to_visit.pop() {
+        for &next in edges.get(&vnum).into_iter().flatten() {
+            if seen.insert(next) {
+                to_visit.push(next);
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod test {
+    use super::reachability;
+    use crate::analysis::{DoorPolicy, Traveler};
+    use crate::model::{Direction, Door, Room, SectorTable};
+    use crate::parser::rooms_by_vnum;
+    use std::rc::Rc;
+
+    #[test]
+    fn finds_unreachable_rooms_and_traps() {
+        use Direction::*;
+        let locked = Door::from_bits(1 | 2 | 4);
+        let rooms = rooms_by_vnum(
+            &[
+                Room::test(1, &[(North, 2), (East, 5)]).with_door(East, locked),
+                Room::test(2, &[(South, 1), (Down, 3), (Up, 6)]),
+                // A one-way drop with no way back but into another area
+                Room::test(3, &[(West, 9000)]),
+                Room::test(4, &[(South, 2)]),
+                Room::test(5, &[(West, 1)]).with_door(West, locked),
+                // A one-way drop into a room with no exits at all
+                Room::test(6, &[]),
+            ]
+            .map(Rc::new),
+        );
+        let sectors = SectorTable::default();
+
+        let found = reachability(&rooms, &sectors, &Traveler::default(), &[1]);
+        assert_eq!(found.unreachable, vec![4]);
+        assert_eq!(found.traps, vec![6]);
+
+        let no_keys = Traveler {
+            doors: DoorPolicy::Unlocked,
+            ..Traveler::default()
+        };
+        let found = reachability(&rooms, &sectors, &no_keys, &[1]);
+        assert_eq!(found.unreachable, vec![4, 5]);
+        assert_eq!(found.traps, vec![6]);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::model::{Direction, Door, Requirement, Room, RoomExit, SectorTable, Vnum};
+use fnv::FnvHashMap;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Which doors a route may go through
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DoorPolicy {
+    Any,
+    /// Closed doors are fine, as long as they aren't locked
+    Unlocked,
+    /// Only doors that are left open
+    Open,
+}
+
+/// What a character can do, and which exits they'd rather not take
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Traveler {
+    pub boat: bool,
+    pub flight: bool,
+    pub doors: DoorPolicy,
+    pub one_way_exits: bool,
+}
+
+impl Default for Traveler {
+    fn default() -> Self {
+        Traveler {
+            boat: true,
+            flight: true,
+            doors: DoorPolicy::Any,
+            one_way_exits: true,
+        }
+    }
+}
+
+impl Traveler {
+    /// Whether `exit` out of `here` into `there` is one the traveler can and will take
+    pub fn can_take(
+        &self,
+        here: &Room,
+        exit: &RoomExit,
+        there: &Room,
+        sectors: &SectorTable,
+    ) -> bool {
+        let one_way = !there
+            .exits
+            .values()
+            .any(|back| back.destination == here.vnum);
+        self.can_pass(exit.door)
+            && (self.one_way_exits || !one_way)
+            && self.meets(sectors.style(there.sector).needs)
+    }
+
+    pub fn can_pass(&self, door: Door) -> bool {
+        match self.doors {
+            DoorPolicy::Any => true,
+            DoorPolicy::Unlocked => !door.locked,
+            DoorPolicy::Open => !door.closed && !door.locked,
+        }
+    }
+
+    fn meets(&self, requirement: Option<Requirement>) -> bool {
+        match requirement {
+            None => true,
+            // Flying gets you over water as well
+            Some(Requirement::Boat) => self.boat || self.flight,
+            Some(Requirement::Flight) => self.flight,
+        }
+    }
+}
+
+/// One way of walking from a room to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// Every room along the way, both ends included
+    pub rooms: Vec<Vnum>,
+    /// The exit taken out of each room but the last
+    pub steps: Vec<Direction>,
+    /// Movement points it takes to walk
+    pub cost: u32,
+}
+
+impl Route {
+    /// The steps as a speedwalk, like `3n2e u w`. Repeated steps are counted, and only runs that
+    /// both have a count are written without a space between them.
+    pub fn speedwalk(&self) -> String {
+        let mut runs: Vec<(usize, Direction)> = vec![];
+        for &step in &self.steps {
+            match runs.last_mut() {
+                Some((count, direction)) if *direction == step => *count += 1,
+                _ => runs.push((1, step)),
+            }
+        }
+
+        let mut speedwalk = String::new();
+        let mut counted = false;
+        for (count, direction) in runs {
+            let joined = counted && count > 1;
+            if !speedwalk.is_empty() && !joined {
+                speedwalk.push(' ');
+            }
+            if count > 1 {
+                speedwalk.push_str(&count.to_string());
+            }
+            speedwalk.push_str(direction.command());
+            counted = count > 1;
+        }
+        speedwalk
+    }
+}
+
+/// Finds the route from `from` to `to` that costs `traveler` the fewest movement points, or `None`
+/// if there isn't one. Like ROM, a step costs the average of the two rooms' sector costs. Exits to
+/// rooms `room` doesn't know are never taken.
+pub fn find_route<'a>(
+    room: impl Fn(Vnum) -> Option<&'a Room>,
+    sectors: &SectorTable,
+    traveler: &Traveler,
+    from: Vnum,
+    to: Vnum,
+) -> Option<Route> {
+    // The cheapest way each room has been reached so far
+    let mut came_from: FnvHashMap<Vnum, (u32, Option<(Vnum, Direction)>)> = FnvHashMap::default();
+    came_from.insert(from, (0, None));
+    let mut queue = BinaryHeap::from([Reverse((0, from))]);
+    while let Some(Reverse((cost, vnum))) = queue.pop() {
+        if vnum == to {
+            break;
+        }
+        if cost > came_from[&vnum].0 {
+            continue;
+        }
+        let here = room(vnum)?;
+        let mut exits: Vec<_> = here.exits.iter().collect();
+        // Exits are stored in a hash map, so sort them for the same route every time
+        exits.sort_by_key(|(&direction, _)| direction);
+        for (&direction, exit) in exits {
+            let there = match room(exit.destination) {
+                Some(there) => there,
+                None => continue,
+            };
+            if !traveler.can_take(here, exit, there, sectors) {
+                continue;
+            }
+
+            let step =
+                (sectors.style(here.sector).move_cost + sectors.style(there.sector).move_cost) / 2;
+            let cost = cost + step;
+            match came_from.get(&exit.destination) {
+                Some(&(known, _)) if known <= cost => {}
+                _ => {
+                    came_from.insert(exit.destination, (cost, Some((vnum, direction))));
+                    queue.push(Reverse((cost, exit.destination)));
+                }
+            }
+        }
+    }
+
+    let (cost, _) = *came_from.get(&to)?;
+    let mut rooms = vec![to];
+    let mut steps = vec![];
+    while let (_, Some((previous, direction))) = came_from[rooms.last().unwrap()] {
+        rooms.push(previous);
+        steps.push(direction);
+    }
+    rooms.reverse();
+    steps.reverse();
+    Some(Route { rooms, steps, cost })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_route, DoorPolicy, Route, Traveler};
+    use crate::model::{Direction, Door, Room, Sector, SectorTable, Vnum};
+    use fnv::FnvHashMap;
+
+    fn sector_room(vnum: Vnum, sector: Sector, exits: &[(Direction, Vnum)]) -> Room {
+        Room {
+            sector,
+            ..Room::test(vnum, exits)
+        }
+    }
+
+    #[test]
+    fn finds_shortest_route() {
+        use Direction::*;
+        let rooms: FnvHashMap<_, _> = [
+            Room::test(1, &[(North, 2), (East, 5)]),
+            Room::test(2, &[(North, 3)]),
+            Room::test(3, &[(East, 4), (Up, 9000)]),
+            Room::test(4, &[(South, 5)]),
+            // One way, so the route from 1 to 4 goes the long way around
+            Room::test(5, &[(West, 1)]),
+            Room::test(6, &[]),
+        ]
+        .into_iter()
+        .map(|room| (room.vnum, room))
+        .collect();
+        let sectors = SectorTable::default();
+        let traveler = Traveler::default();
+        let route = |from, to| find_route(|vnum| rooms.get(&vnum), &sectors, &traveler, from, to);
+
+        let found = route(1, 4).unwrap();
+        assert_eq!(found.rooms, vec![1, 2, 3, 4]);
+        assert_eq!(found.speedwalk(), "2n e");
+        assert_eq!(found.cost, 3);
+        assert_eq!(route(4, 1).unwrap().rooms, vec![4, 5, 1]);
+        assert_eq!(route(1, 1).unwrap().steps, vec![]);
+        assert_eq!(route(1, 6), None);
+        assert_eq!(route(1, 9000), None);
+    }
+
+    #[test]
+    fn counts_repeated_steps() {
+        use Direction::*;
+        let route = Route {
+            rooms: vec![],
+            steps: vec![
+                North, North, North, East, East, Up, West, Northeast, Northeast,
+            ],
+            cost: 0,
+        };
+        assert_eq!(route.speedwalk(), "3n2e u w 2ne");
+    }
+
+    #[test]
+    fn weighs_routes_by_what_the_traveler_can_do() {
+        use Direction::*;
+        use Sector::*;
+        let locked = Door::from_bits(1 | 2 | 4);
+        // North over the mountain is steep, east across the lake needs a boat, and west is up in
+        // the air behind locked doors
+        let rooms: FnvHashMap<_, _> = [
+            sector_room(1, Field, &[(North, 2), (East, 3), (West, 5)]),
+            sector_room(2, Mountain, &[(South, 1), (North, 4)]),
+            sector_room(3, WaterNoswim, &[(West, 1), (North, 4)]),
+            sector_room(4, Field, &[(South, 2), (East, 3)]),
+            sector_room(5, Air, &[(East, 1), (Up, 6)])
+                .with_door(East, locked)
+                .with_door(Up, locked),
+            sector_room(6, Field, &[]),
+        ]
+        .into_iter()
+        .map(|room| (room.vnum, room))
+        .collect();
+        let sectors = SectorTable::default();
+        let route = |traveler: Traveler, to| {
+            find_route(|vnum| rooms.get(&vnum), &sectors, &traveler, 1, to)
+                .map(|route| (route.rooms, route.cost))
+        };
+
+        let anyone = Traveler::default();
+        assert_eq!(route(anyone, 4), Some((vec![1, 3, 4], 2)));
+        let no_boat = Traveler {
+            boat: false,
+            flight: false,
+            ..anyone
+        };
+        assert_eq!(route(no_boat, 4), Some((vec![1, 2, 4], 8)));
+        assert_eq!(route(no_boat, 5), None);
+        // 5 leads on to 6, but never back
+        assert_eq!(route(anyone, 6), Some((vec![1, 5, 6], 12)));
+        let careful = Traveler {
+            doors: DoorPolicy::Unlocked,
+            ..anyone
+        };
+        assert_eq!(route(careful, 6), None);
+        let two_way = Traveler {
+            one_way_exits: false,
+            ..anyone
+        };
+        assert_eq!(route(two_way, 6), None);
+        assert_eq!(route(two_way, 5), Some((vec![1, 5], 6)));
+    }
+}
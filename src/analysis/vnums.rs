@@ -0,0 +1,115 @@
+use crate::model::{strip_color_codes, Vnum};
+use crate::parser::ParsedArea;
+use serde::Serialize;
+
+/// Which of an area's vnums are taken by one kind of thing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VnumUsage {
+    pub count: usize,
+    /// Runs of vnums in use, first and last
+    pub used: Vec<(Vnum, Vnum)>,
+    /// Runs of free vnums between, before and after them
+    pub gaps: Vec<(Vnum, Vnum)>,
+    /// The first vnum of the first gap big enough for the block asked for
+    pub next_block: Option<Vnum>,
+}
+
+/// Vnum usage of one of the loaded areas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AreaVnums {
+    /// Without its color codes
+    pub name: String,
+    pub range: (Vnum, Vnum),
+    pub rooms: VnumUsage,
+    pub mobiles: VnumUsage,
+    pub objects: VnumUsage,
+}
+
+/// Works out what's free in each area's vnum range, looking for gaps of at least `block` vnums.
+/// Mobiles and objects count towards whichever area's range they fall in, since vnums are shared
+/// between all areas.
+pub fn vnum_report(area: &ParsedArea, block: u32) -> Vec<AreaVnums> {
+    let sorted = |vnums: &mut Vec<Vnum>| {
+        vnums.sort_unstable();
+        vnums.dedup();
+    };
+    let mut rooms: Vec<_> = area.all_rooms.keys().copied().collect();
+    let mut mobiles: Vec<_> = area.mobiles.iter().map(|mobile| mobile.vnum).collect();
+    let mut objects: Vec<_> = area.objects.iter().map(|object| object.vnum).collect();
+    sorted(&mut rooms);
+    sorted(&mut mobiles);
+    sorted(&mut objects);
+
+    (0..area.areas.len())
+        .filter_map(|idx| {
+            let range = area.vnum_range(idx)?;
+            let header = &area.areas[idx].header;
+            Some(AreaVnums {
+                name: strip_color_codes(&header.name, header.color_codes),
+                range,
+                rooms: vnum_usage(&rooms, range, block),
+                mobiles: vnum_usage(&mobiles, range, block),
+                objects: vnum_usage(&objects, range, block),
+            })
+        })
+        .collect()
+}
+
+/// Usage of the `range` by the sorted `vnums`
+fn vnum_usage(vnums: &[Vnum], (low, high): (Vnum, Vnum), block: u32) -> VnumUsage {
+    let start = vnums.partition_point(|&vnum| vnum < low);
+    let end = vnums.partition_point(|&vnum| vnum <= high);
+    let vnums = &vnums[start..end];
+
+    let mut used: Vec<(Vnum, Vnum)> = vec![];
+    for &vnum in vnums {
+        match used.last_mut() {
+            Some((_, last)) if *last + 1 == vnum => *last = vnum,
+            _ => used.push((vnum, vnum)),
+        }
+    }
+
+    let mut gaps = vec![];
+    // `None` once the vnums run out altogether
+    let mut free_from = Some(low);
+    for &(first, last) in &used {
+        match free_from {
+            Some(from) if first > from => gaps.push((from, first - 1)),
+            _ => {}
+        }
+        free_from = last.checked_add(1);
+    }
+    match free_from {
+        Some(from) if from <= high => gaps.push((from, high)),
+        _ => {}
+    }
+
+    let next_block = gaps
+        .iter()
+        .find(|&&(first, last)| last - first >= block.saturating_sub(1))
+        .map(|&(first, _)| first);
+    VnumUsage {
+        count: vnums.len(),
+        used,
+        gaps,
+        next_block,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::vnum_usage;
+
+    #[test]
+    fn finds_gaps_and_free_blocks() {
+        let usage = vnum_usage(&[90, 100, 101, 102, 105, 110, 111, 200], (100, 119), 4);
+        assert_eq!(usage.count, 6);
+        assert_eq!(usage.used, vec![(100, 102), (105, 105), (110, 111)]);
+        assert_eq!(usage.gaps, vec![(103, 104), (106, 109), (112, 119)]);
+        assert_eq!(usage.next_block, Some(106));
+
+        let usage = vnum_usage(&[], (100, 119), 30);
+        assert_eq!(usage.gaps, vec![(100, 119)]);
+        assert_eq!(usage.next_block, None);
+    }
+}
@@ -1,8 +1,8 @@
-use crate::model::Door;
+use crate::model::{strip_color_codes, Door};
 use crate::{Connection, Direction, Exit, Model};
 use nannou::prelude::*;
 
-const CONNECTION_LABELS: &'static [&'static str] = &[
+const CONNECTION_LABELS: &[&str] = &[
     "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S",
     "T", "U", "V", "W", "Z", "Y", "Z", "Γ", "Δ", "Θ", "Λ", "Ξ", "Π", "Σ", "Φ", "Ψ", "Ω",
 ];
@@ -18,8 +18,9 @@ pub fn draw_connections(draw: &Draw, model: &Model) {
                     let symbol = endcap_symbol.next().unwrap();
                     draw_disconnected_connection(draw, model, from, to, symbol, *door);
                 } else {
-                    draw_connection(&draw, &model, from, to, false, *door);
+                    draw_connection(draw, model, from, to, false, *door);
                 }
+                draw_area_labels(draw, model, from, to);
             }
             Connection::OneWay { from, to, door } => {
                 if is_updown_connection(from, to)
@@ -28,11 +29,12 @@ pub fn draw_connections(draw: &Draw, model: &Model) {
                     let symbol = endcap_symbol.next().unwrap();
                     draw_disconnected_connection(draw, model, from, to, symbol, *door);
                 } else {
-                    draw_connection(&draw, &model, from, to, true, *door);
+                    draw_connection(draw, model, from, to, true, *door);
                 }
+                draw_area_labels(draw, model, from, to);
             }
             Connection::External { from, to, door } => {
-                draw_external_connection(&draw, &model, from, &to, *door);
+                draw_external_connection(draw, model, from, to, *door);
             }
         }
     }
@@ -45,9 +47,16 @@ fn draw_connection(draw: &Draw, model: &Model, from: &Exit, to: &Exit, one_way:
         .weight(2f32)
         .join_round()
         .points(vec![p1, p2, p3, p4]);
-    if let Door::Closed = door {
-        draw_door_between(draw, p1, p2, p4, p3);
+    // There's no way back, so the end going into the room gets an arrowhead
+    if one_way {
+        draw.arrow()
+            .weight(2f32)
+            .head_length(model.square_size() * 0.25)
+            .head_width(model.square_size() * 0.15)
+            .start(p3)
+            .end(p4);
     }
+    draw_door_between(draw, p1, p2, p4, p3, door);
 }
 
 fn draw_external_connection(draw: &Draw, model: &Model, exit: &Exit, text: &str, door: Door) {
@@ -55,28 +64,67 @@ fn draw_external_connection(draw: &Draw, model: &Model, exit: &Exit, text: &str,
     let delta = p2 - p1;
     draw.line().stroke_weight(2f32).start(p1).end(p2);
     draw.xy(p2 + delta * 0.5).text(text).color(RED);
-    if let Door::Closed = door {
-        draw_perpendicular_line_between(draw, p1, p2);
+    draw_perpendicular_line_between(draw, p1, p2, door);
+}
+
+/// Connections between loaded areas are labelled at each end with the area on the other side
+fn draw_area_labels(draw: &Draw, model: &Model, from: &Exit, to: &Exit) {
+    let from_area = model.room_areas[from.index];
+    let to_area = model.room_areas[to.index];
+    if from_area == to_area {
+        return;
+    }
+    for (exit, area) in [(from, to_area), (to, from_area)] {
+        let (p1, p2) = find_exit(model, exit, Lean::None);
+        draw.xy(p2 + (p2 - p1) * 0.5)
+            .text(&strip_color_codes(
+                &model.areas[area].name,
+                model.areas[area].color_codes,
+            ))
+            .no_line_wrap()
+            .color(DARKBLUE);
     }
 }
 
-fn draw_door_between(draw: &Draw, f0: Vec2, f1: Vec2, t0: Vec2, t1: Vec2) {
+fn draw_door_between(draw: &Draw, f0: Vec2, f1: Vec2, t0: Vec2, t1: Vec2, door: Door) {
     // This threshold is arbitrary
     if (f1 - t1).length() > 15f32 {
-        draw_perpendicular_line_between(draw, f1, t1);
+        draw_perpendicular_line_between(draw, f1, t1, door);
     } else {
-        draw_perpendicular_line_between(draw, f0, t0);
+        draw_perpendicular_line_between(draw, f0, t0, door);
     }
 }
 
-fn draw_perpendicular_line_between(draw: &Draw, p1: Vec2, p2: Vec2) {
+/// Doors are drawn across the connection, colored by the state resets leave them in. Doors that
+/// can't be picked get a heavier line, secret ones a lighter color.
+fn draw_perpendicular_line_between(draw: &Draw, p1: Vec2, p2: Vec2, door: Door) {
+    if !door.is_door {
+        return;
+    }
+    let color = if door.locked {
+        if door.secret {
+            LIGHTCORAL
+        } else {
+            RED
+        }
+    } else if door.closed {
+        if door.secret {
+            GRAY
+        } else {
+            BLACK
+        }
+    } else {
+        DARKGRAY
+    };
+    let weight = if door.pickproof { 4f32 } else { 2f32 };
     let middle = (p1 + p2) * 0.5;
     let delta = p2 - p1;
-    let inverted = Vec2::new(delta.y, delta.x * -1f32).normalize_or_zero() * 10f32;
+    let inverted = Vec2::new(delta.y, -delta.x).normalize_or_zero() * 10f32;
     draw.line()
-        .stroke_weight(2f32)
+        .stroke_weight(weight)
         .start(middle - inverted)
         .end(middle + inverted)
+        .color(color)
         .finish();
 }
 
@@ -88,8 +136,8 @@ fn draw_disconnected_connection(
     label: &str,
     door: Door,
 ) {
-    let x1 = location_of(model, from.index).x;
-    let x2 = location_of(model, to.index).x;
+    let x1 = model.location_of(from.index).x;
+    let x2 = model.location_of(to.index).x;
 
     let (p1, p2) = find_exit(model, from, if x1 < x2 { Lean::Right } else { Lean::Left });
     draw.line().stroke_weight(2f32).start(p1).end(p2);
@@ -111,10 +159,8 @@ fn draw_disconnected_connection(
         .finish();
     draw.xy(p4).text(label).color(BLACK);
 
-    if let Door::Closed = door {
-        draw_perpendicular_line_between(draw, p1, p2);
-        draw_perpendicular_line_between(draw, p4, p3);
-    }
+    draw_perpendicular_line_between(draw, p1, p2, door);
+    draw_perpendicular_line_between(draw, p4, p3, door);
 }
 
 enum Lean {
@@ -124,20 +170,34 @@ enum Lean {
 }
 
 fn find_exit(model: &Model, exit: &Exit, lean: Lean) -> (Vec2, Vec2) {
-    let center = location_of(model, exit.index);
+    let center = model.location_of(exit.index);
     let half_size = model.square_size() * 0.5;
+    let quarter_size = half_size * 0.5;
     let delta = match exit.direction {
         Direction::North => Vec2::new(0f32, half_size),
         Direction::East => Vec2::new(half_size, 0f32),
-        Direction::South => Vec2::new(0f32, half_size * -1f32),
-        Direction::West => Vec2::new(half_size * -1f32, 0f32),
+        Direction::South => Vec2::new(0f32, -half_size),
+        Direction::West => Vec2::new(-half_size, 0f32),
+        Direction::Northeast => Vec2::new(half_size, half_size),
+        Direction::Northwest => Vec2::new(-half_size, half_size),
+        Direction::Southeast => Vec2::new(half_size, -half_size),
+        Direction::Southwest => Vec2::new(-half_size, -half_size),
+        // The corners are taken by the diagonals, so the rest leave from the edges off-center
         Direction::Up => match lean {
-            Lean::None | Lean::Right => Vec2::default() + half_size,
-            Lean::Left => Vec2::new(half_size * -1f32, half_size),
+            Lean::None | Lean::Right => Vec2::new(quarter_size, half_size),
+            Lean::Left => Vec2::new(-quarter_size, half_size),
         },
         Direction::Down => match lean {
-            Lean::None | Lean::Left => Vec2::default() - half_size,
-            Lean::Right => Vec2::new(half_size, half_size * -1f32),
+            Lean::None | Lean::Left => Vec2::new(-quarter_size, -half_size),
+            Lean::Right => Vec2::new(quarter_size, -half_size),
+        },
+        Direction::In => match lean {
+            Lean::None | Lean::Right => Vec2::new(half_size, -quarter_size),
+            Lean::Left => Vec2::new(-half_size, -quarter_size),
+        },
+        Direction::Out => match lean {
+            Lean::None | Lean::Left => Vec2::new(-half_size, quarter_size),
+            Lean::Right => Vec2::new(half_size, quarter_size),
         },
     };
     let start = center + delta;
@@ -145,23 +205,17 @@ fn find_exit(model: &Model, exit: &Exit, lean: Lean) -> (Vec2, Vec2) {
     (start, end)
 }
 
+/// Up, down, in and out don't point anywhere on the map, so they're drawn like a jump
 fn is_updown_connection(left: &Exit, right: &Exit) -> bool {
-    match (left.direction, right.direction) {
-        (Direction::Up, _) | (Direction::Down, _) | (_, Direction::Up) | (_, Direction::Down) => {
-            true
-        }
-        _ => false,
-    }
+    let off_map = |direction| {
+        matches!(
+            direction,
+            Direction::Up | Direction::Down | Direction::In | Direction::Out
+        )
+    };
+    off_map(left.direction) || off_map(right.direction)
 }
 
 fn is_cross_plane_connection(left: usize, right: usize, model: &Model) -> bool {
     model.room_planes[left] != model.room_planes[right]
 }
-
-fn location_of(model: &Model, index: usize) -> Vec2 {
-    if model.selected[index] {
-        model.locations[index] + model.ui.grab_offset.unwrap_or_default()
-    } else {
-        model.locations[index]
-    }
-}
@@ -0,0 +1,61 @@
+use crate::parser::{Diagnostic, Severity};
+use nannou::prelude::*;
+
+const PANEL_WIDTH: f32 = 480f32;
+const PANEL_PADDING: f32 = 10f32;
+const LINE_HEIGHT: f32 = 16f32;
+const FONT_SIZE: u32 = 12;
+const MAX_LINES: usize = 8;
+
+/// Lists whatever went wrong while loading the area in the bottom left corner of the window
+pub fn draw_diagnostics(draw: &Draw, window: Rect, diagnostics: &[Diagnostic]) {
+    if diagnostics.is_empty() {
+        return;
+    }
+
+    let mut lines: Vec<_> = diagnostics
+        .iter()
+        .take(MAX_LINES)
+        .map(|d| {
+            let color = match d.severity {
+                Severity::Error => RED,
+                Severity::Warning => DARKORANGE,
+            };
+            let text = if d.line == 0 {
+                format!("{}: {}", d.severity, d.message)
+            } else {
+                format!("{}:{}: {}: {}", d.line, d.column, d.severity, d.message)
+            };
+            (text, color)
+        })
+        .collect();
+    if diagnostics.len() > MAX_LINES {
+        lines.push((
+            format!("...and {} more", diagnostics.len() - MAX_LINES),
+            BLACK,
+        ));
+    }
+
+    let panel_height = lines.len() as f32 * LINE_HEIGHT + PANEL_PADDING * 2f32;
+    let panel = Rect::from_w_h(PANEL_WIDTH, panel_height)
+        .bottom_left_of(window)
+        .shift(Vec2::new(PANEL_PADDING, PANEL_PADDING));
+    draw.xy(panel.xy())
+        .rect()
+        .wh(panel.wh())
+        .color(rgba(1f32, 1f32, 1f32, 0.9f32))
+        .stroke(BLACK)
+        .stroke_weight(1f32);
+
+    let text_width = PANEL_WIDTH - PANEL_PADDING * 2f32;
+    for (i, (text, color)) in lines.iter().enumerate() {
+        let y = panel.top() - PANEL_PADDING - (i as f32 + 0.5) * LINE_HEIGHT;
+        draw.xy(Vec2::new(panel.x(), y))
+            .text(text)
+            .w_h(text_width, LINE_HEIGHT)
+            .font_size(FONT_SIZE)
+            .left_justify()
+            .no_line_wrap()
+            .color(*color);
+    }
+}
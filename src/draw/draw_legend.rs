@@ -1,45 +1,41 @@
-use crate::model::Sector;
+use crate::model::AreaHeader;
 use crate::LabelColor;
+use nannou::color::named::BLACK;
 use nannou::prelude::Vec2;
 use nannou::Draw;
 
-const LEGEND_SECTORS: &'static [(Sector, &'static str)] = &[
-    (Sector::Inside, "Inside"),
-    (Sector::House, "House"),
-    (Sector::City, "City"),
-    (Sector::RogueGuild, "Rogue Guild"),
-    (Sector::Field, "Field"),
-    (Sector::Hills, "Hills"),
-    (Sector::Forest, "Forest"),
-    (Sector::Mountain, "Mountain"),
-    (Sector::Desert, "Desert"),
-    (Sector::WaterSwim, "Water (swim)"),
-    (Sector::WaterNoswim, "Water (no swim)"),
-    (Sector::Underwater, "Underwater"),
-    (Sector::OnBottom, "On Bottom"),
-    (Sector::Air, "Air"),
-];
-
-pub fn draw_legend(draw: &Draw, sectors: &[Sector]) {
+/// Lists the header of the area, and what the colors of the rooms mean
+pub fn draw_legend(draw: &Draw, areas: &[AreaHeader], keys: &[(String, LabelColor)]) {
     const CELL_WIDTH: f32 = 100f32;
     const CELL_HEIGHT: f32 = 20f32;
-    for (y, (sector, sector_name)) in LEGEND_SECTORS
-        .iter()
-        .filter(|(s, _)| sectors.contains(s))
-        .enumerate()
-    {
-        let xy = Vec2::new(5f32, -5f32 - (y) as f32 * (CELL_HEIGHT + 5f32));
+
+    let header_lines = match areas {
+        [header] => header.summary(),
+        _ => vec![format!("{} areas", areas.len())],
+    };
+    for (y, line) in header_lines.iter().enumerate() {
+        let xy = Vec2::new(5f32, -5f32 - y as f32 * CELL_HEIGHT);
+        draw.xy(xy + Vec2::new(CELL_WIDTH * 1.5, -CELL_HEIGHT * 0.5))
+            .text(line)
+            .w_h(CELL_WIDTH * 3f32, CELL_HEIGHT)
+            .left_justify()
+            .color(BLACK);
+    }
+    let keys_top = -5f32 - header_lines.len() as f32 * CELL_HEIGHT;
+
+    for (y, (name, color)) in keys.iter().enumerate() {
+        let xy = Vec2::new(5f32, keys_top - (y) as f32 * (CELL_HEIGHT + 5f32));
         let LabelColor {
             background,
             foreground,
-        } = sector.color();
-        let cell_center: Vec2 = Vec2::new(CELL_WIDTH, CELL_HEIGHT * -1f32) * 0.5;
+        } = *color;
+        let cell_center: Vec2 = Vec2::new(CELL_WIDTH, -CELL_HEIGHT) * 0.5;
         draw.xy(xy + cell_center)
             .rect()
             .w_h(CELL_WIDTH, CELL_HEIGHT)
             .color(background);
         draw.xy(xy + cell_center + 2f32)
-            .text(sector_name)
+            .text(name)
             .w_h(CELL_WIDTH - 4f32, CELL_HEIGHT - 4f32)
             .left_justify()
             .color(foreground);
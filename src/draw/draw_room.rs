@@ -1,5 +1,9 @@
+use crate::analysis::LintKind;
+use crate::model::RoomFlag;
 use crate::{LabelColor, Model};
-use nannou::color::named::BLACK;
+use nannou::color::named::{BLACK, DARKORANGE, DARKRED, DARKVIOLET, RED, WHITE};
+use nannou::color::rgba;
+use nannou::prelude::Vec2;
 use nannou::Draw;
 
 pub fn draw_rooms(draw: &Draw, model: &Model) {
@@ -26,22 +30,81 @@ pub fn draw_rooms(draw: &Draw, model: &Model) {
                 .finish();
         }
 
+        // Rooms stacked on top of each other get a ring that shows past whichever is drawn last
+        let overlapping = model
+            .lints
+            .get(&room.vnum)
+            .into_iter()
+            .flatten()
+            .any(|lint| lint.kind == LintKind::OverlappingRooms);
+        if overlapping {
+            rdraw
+                .rect()
+                .w_h(model.square_size() * 1.4, model.square_size() * 1.4)
+                .no_fill()
+                .stroke(DARKVIOLET)
+                .stroke_weight(4f32)
+                .finish();
+        }
+
         let LabelColor {
             background,
             foreground,
-        } = room.sector.color();
+        } = model.room_color(room);
 
         rdraw
             .rect()
             .w_h(model.square_size(), model.square_size())
             .color(background);
+        // Death traps get a red outline so they're hard to miss
+        let outline = if room.flags.contains(RoomFlag::Death) {
+            RED
+        } else {
+            BLACK
+        };
         rdraw
             .rect()
             .w_h(model.square_size(), model.square_size())
             .no_fill()
-            .stroke(BLACK)
+            .stroke(outline)
             .stroke_weight(2f32)
             .finish();
         rdraw.text(&room.string_vnum).color(foreground);
+
+        let half = model.square_size() * 0.5;
+        if model.unreachable.contains(&room.vnum) {
+            // Faded out, since nobody will ever see them
+            rdraw
+                .rect()
+                .w_h(model.square_size(), model.square_size())
+                .color(rgba(1f32, 1f32, 1f32, 0.6f32));
+        }
+        if model.traps.contains(&room.vnum) {
+            // Crossed out, since whoever gets in doesn't get out
+            for (start, end) in [
+                (Vec2::new(-half, -half), Vec2::new(half, half)),
+                (Vec2::new(-half, half), Vec2::new(half, -half)),
+            ] {
+                rdraw
+                    .line()
+                    .start(start)
+                    .end(end)
+                    .stroke_weight(3f32)
+                    .color(DARKRED);
+            }
+        }
+
+        // Rooms the linter has something to say about get a badge in the corner
+        if model.lints.contains_key(&room.vnum) {
+            let corner = Vec2::splat(model.square_size() * 0.5);
+            rdraw
+                .xy(corner)
+                .ellipse()
+                .radius(model.square_size() * 0.2)
+                .color(DARKORANGE)
+                .stroke(BLACK)
+                .stroke_weight(1f32);
+            rdraw.xy(corner).text("!").font_size(10).color(WHITE);
+        }
     }
 }
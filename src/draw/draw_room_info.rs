@@ -0,0 +1,264 @@
+use crate::model::{
+    affect_location_name, flag_letters, wear_location_name, ColorCodes, ColoredText, Hue, Mobile,
+    Model, Object, Room, SpawnedObject, TextColor, Vnum,
+};
+use nannou::prelude::*;
+
+const PANEL_WIDTH: f32 = 320f32;
+const PANEL_PADDING: f32 = 10f32;
+const FONT_SIZE: u32 = 12;
+
+pub fn draw_room_info(draw: &Draw, window: Rect, model: &Model, idx: usize) {
+    let mut text = ColoredText::default();
+    let area = &model.areas[model.room_areas[idx]];
+    if model.areas.len() > 1 {
+        text.push_coded(&area.name, area.color_codes);
+        text.push_plain("\n");
+    }
+    push_room_info(&mut text, model, &model.rooms[idx], area.color_codes);
+    let text_width = PANEL_WIDTH - PANEL_PADDING * 2f32;
+
+    // Measure first so the panel can be sized to fit the wrapped text
+    let text_height = nannou::text::text(&text.text)
+        .font_size(FONT_SIZE)
+        .left_justify()
+        .build(Rect::from_w_h(text_width, window.h()))
+        .height()
+        .min(window.h() - PANEL_PADDING * 4f32);
+    let panel_height = text_height + PANEL_PADDING * 2f32;
+
+    let panel = Rect::from_w_h(PANEL_WIDTH, panel_height)
+        .top_right_of(window)
+        .shift(Vec2::new(-PANEL_PADDING, -PANEL_PADDING));
+    draw.xy(panel.xy())
+        .rect()
+        .wh(panel.wh())
+        .color(rgba(1f32, 1f32, 1f32, 0.9f32))
+        .stroke(BLACK)
+        .stroke_weight(1f32);
+    draw.xy(panel.xy())
+        .text(&text.text)
+        .wh(Vec2::new(text_width, text_height))
+        .font_size(FONT_SIZE)
+        .left_justify()
+        .align_text_top()
+        .glyph_colors(text.colors.iter().map(|&color| on_light_background(color)));
+}
+
+/// MUD colors are meant for a black terminal, so they're darkened to stay readable on the panel
+fn on_light_background(color: Option<TextColor>) -> Rgb8 {
+    let (hue, bright) = match color {
+        Some(TextColor { hue, bright }) => (hue, bright),
+        None => return BLACK,
+    };
+    match (hue, bright) {
+        (Hue::Black, false) => BLACK,
+        (Hue::Black, true) => DIMGRAY,
+        (Hue::Red, false) => DARKRED,
+        (Hue::Red, true) => RED,
+        (Hue::Green, false) => DARKGREEN,
+        (Hue::Green, true) => GREEN,
+        (Hue::Yellow, false) => DARKGOLDENROD,
+        (Hue::Yellow, true) => GOLDENROD,
+        (Hue::Blue, false) => NAVY,
+        (Hue::Blue, true) => BLUE,
+        (Hue::Magenta, false) => PURPLE,
+        (Hue::Magenta, true) => MAGENTA,
+        (Hue::Cyan, false) => TEAL,
+        (Hue::Cyan, true) => DARKCYAN,
+        (Hue::White, false) => GRAY,
+        (Hue::White, true) => DARKGRAY,
+    }
+}
+
+/// Mobiles and objects are taken to be written with the same `codes` as the room they reset in
+fn push_room_info(text: &mut ColoredText, model: &Model, room: &Room, codes: ColorCodes) {
+    text.push_plain(&format!("#{} ", room.vnum));
+    text.push_coded(room.name.trim(), codes);
+
+    let flags: Vec<_> = room.flags.iter().map(|f| f.name()).collect();
+    if !flags.is_empty() {
+        text.push_plain(&format!("\nFlags: {}", flags.join(", ")));
+    }
+
+    let description = room.description.trim();
+    if !description.is_empty() {
+        text.push_plain("\n\n");
+        text.push_coded(description, codes);
+    }
+
+    for extra in &room.extra_descriptions {
+        text.push_plain(&format!("\n\n[{}] ", extra.keywords.trim()));
+        text.push_coded(extra.description.trim(), codes);
+    }
+
+    let mut exits: Vec<_> = room.exits.iter().collect();
+    exits.sort_by_key(|(direction, _)| **direction);
+    if !exits.is_empty() {
+        text.push_plain("\n\nExits:");
+    }
+    for (direction, exit) in exits {
+        text.push_plain(&format!("\n  {:?} to #{}", direction, exit.destination));
+        if exit.door.is_door {
+            text.push_plain(&format!(", {}", exit.door.describe()));
+            let keywords = exit.keywords.trim();
+            if !keywords.is_empty() {
+                text.push_plain(&format!(" [{keywords}]"));
+            }
+        }
+        if let Some(key) = exit.key {
+            text.push_plain("\n    key: ");
+            push_object_name(text, model, key, codes);
+        }
+        let description = exit.description.trim();
+        if !description.is_empty() {
+            text.push_plain("\n    ");
+            text.push_coded(description, codes);
+        }
+    }
+
+    if let Some(lints) = model.lints.get(&room.vnum) {
+        text.push_plain("\n\nLint:");
+        for lint in lints {
+            text.push_plain(&format!("\n  {}", lint.message));
+        }
+    }
+
+    if let Some(changes) = model.changes.get(&room.vnum) {
+        text.push_plain("\n\nChanges:");
+        for change in changes {
+            text.push_plain(&format!("\n  {change}"));
+        }
+    }
+
+    if model.unreachable.contains(&room.vnum) {
+        text.push_plain("\n\nCan't be got to from the entrances");
+    }
+    if model.traps.contains(&room.vnum) {
+        text.push_plain("\n\nThere's no way back to an entrance from here");
+    }
+
+    if let Some(spawns) = model.spawns.get(&room.vnum) {
+        text.push_plain("\n\nResets:");
+        for mobile in &spawns.mobiles {
+            text.push_plain("\n  ");
+            match model.mobiles.get(&mobile.vnum) {
+                Some(found) => push_mobile(text, found, codes),
+                None => text.push_plain(&format!("mobile #{}", mobile.vnum)),
+            }
+            for (location, object) in &mobile.equipment {
+                text.push_plain(&format!("\n    {}: ", wear_location_name(*location)));
+                push_object(text, model, object, 4, codes);
+            }
+            for object in &mobile.inventory {
+                text.push_plain("\n    carried: ");
+                push_object(text, model, object, 4, codes);
+            }
+        }
+        for object in &spawns.objects {
+            text.push_plain("\n  ");
+            push_object(text, model, object, 2, codes);
+        }
+        if let Some(exits) = spawns.randomized_exits {
+            text.push_plain(&format!("\n  first {exits} exits are shuffled"));
+        }
+    }
+}
+
+fn push_object(
+    text: &mut ColoredText,
+    model: &Model,
+    object: &SpawnedObject,
+    indent: usize,
+    codes: ColorCodes,
+) {
+    push_object_name(text, model, object.vnum, codes);
+    if let Some(found) = model.objects.get(&object.vnum) {
+        push_object_details(text, model, found, indent + 2, codes);
+    }
+    for content in &object.contents {
+        text.push_plain(&format!("\n{:indent$}  contains: ", ""));
+        push_object(text, model, content, indent + 2, codes);
+    }
+}
+
+/// The mobile's name and level, then what it looks like in the room and what it fights with
+fn push_mobile(text: &mut ColoredText, mobile: &Mobile, codes: ColorCodes) {
+    text.push_coded(mobile.short_description.trim(), codes);
+    text.push_plain(&format!(
+        " (#{}, level {}, alignment {})",
+        mobile.vnum, mobile.level, mobile.alignment
+    ));
+    text.push_plain(&format!("\n    [{}] ", mobile.keywords.trim()));
+    text.push_coded(mobile.long_description.trim(), codes);
+    text.push_plain(&format!(
+        "\n    act {}, affected by {}",
+        flag_letters(mobile.act_flags),
+        flag_letters(mobile.affect_flags)
+    ));
+    text.push_plain(&format!(
+        "\n    {} hp, {} damage, hitroll {}, armor {}",
+        mobile.hit_dice, mobile.damage_dice, mobile.hitroll, mobile.armor
+    ));
+}
+
+/// Details are indented under the object's name by `indent`
+fn push_object_details(
+    text: &mut ColoredText,
+    model: &Model,
+    object: &Object,
+    indent: usize,
+    codes: ColorCodes,
+) {
+    text.push_plain(&format!("\n{:indent$}[{}] ", "", object.keywords.trim()));
+    text.push_coded(object.long_description.trim(), codes);
+    text.push_plain(&format!(
+        "\n{:indent$}{}, weight {}, cost {}, extra {}, wear {}",
+        "",
+        object.item_type,
+        object.weight,
+        object.cost,
+        flag_letters(object.extra_flags),
+        flag_letters(object.wear_flags)
+    ));
+    if let Some(destination) = object.portal_destination() {
+        text.push_plain(&format!("\n{:indent$}leads to ", ""));
+        match model.rooms.iter().find(|room| room.vnum == destination) {
+            Some(room) => {
+                text.push_coded(room.name.trim(), codes);
+                text.push_plain(&format!(" (#{destination})"));
+            }
+            None => text.push_plain(&format!("room #{destination}")),
+        }
+    }
+    if let Some(key) = object.container_key() {
+        text.push_plain(&format!("\n{:indent$}key: ", ""));
+        push_object_name(text, model, key, codes);
+    }
+    for affect in &object.affects {
+        text.push_plain(&format!(
+            "\n{:indent$}{:+} {}",
+            "",
+            affect.modifier,
+            affect_location_name(affect.location)
+        ));
+    }
+    if !object.extra_descriptions.is_empty() {
+        let keywords: Vec<_> = object
+            .extra_descriptions
+            .iter()
+            .map(|extra| format!("[{}]", extra.keywords.trim()))
+            .collect();
+        text.push_plain(&format!("\n{:indent$}look at {}", "", keywords.join(" ")));
+    }
+}
+
+fn push_object_name(text: &mut ColoredText, model: &Model, vnum: Vnum, codes: ColorCodes) {
+    match model.objects.get(&vnum) {
+        Some(object) => {
+            text.push_coded(object.short_description.trim(), codes);
+            text.push_plain(&format!(" (#{vnum})"));
+        }
+        None => text.push_plain(&format!("object #{vnum}")),
+    }
+}
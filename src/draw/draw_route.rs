@@ -0,0 +1,78 @@
+use crate::Model;
+use nannou::prelude::*;
+
+const ROUTE_COLOR: Rgb8 = GOLD;
+const PANEL_PADDING: f32 = 10f32;
+const LINE_HEIGHT: f32 = 16f32;
+const FONT_SIZE: u32 = 12;
+
+/// Outlines the rooms along the route between the two selected rooms, joining those on the same
+/// plane. Drawn under the rooms.
+pub fn draw_route(draw: &Draw, model: &Model) {
+    let route = match model
+        .route
+        .as_ref()
+        .and_then(|selected| selected.route.as_ref())
+    {
+        Some(route) => route,
+        None => return,
+    };
+    let rooms: Vec<_> = route
+        .rooms
+        .iter()
+        .map(|vnum| model.room_indices[vnum])
+        .collect();
+    for pair in rooms.windows(2) {
+        if model.room_planes[pair[0]] == model.room_planes[pair[1]] {
+            draw.line()
+                .start(model.location_of(pair[0]))
+                .end(model.location_of(pair[1]))
+                .stroke_weight(6f32)
+                .color(ROUTE_COLOR);
+        }
+    }
+    for &idx in &rooms {
+        draw.xy(model.location_of(idx))
+            .rect()
+            .w_h(model.square_size() * 1.2, model.square_size() * 1.2)
+            .color(ROUTE_COLOR);
+    }
+}
+
+/// Writes the route between the two selected rooms out as a speedwalk in the top right corner
+/// of the window
+pub fn draw_route_summary(draw: &Draw, window: Rect, model: &Model) {
+    let selected = match &model.route {
+        Some(selected) => selected,
+        None => return,
+    };
+    let from = model.rooms[selected.from].vnum;
+    let to = model.rooms[selected.to].vnum;
+    let text = match &selected.route {
+        Some(route) => format!(
+            "{} to {}: {} ({} moves)",
+            from,
+            to,
+            route.speedwalk(),
+            route.cost
+        ),
+        None => format!("No way from {from} to {to}"),
+    };
+
+    let panel = Rect::from_w_h(window.w() * 0.4, LINE_HEIGHT + PANEL_PADDING * 2f32)
+        .top_right_of(window)
+        .shift(Vec2::new(-PANEL_PADDING, -PANEL_PADDING));
+    draw.xy(panel.xy())
+        .rect()
+        .wh(panel.wh())
+        .color(rgba(1f32, 1f32, 1f32, 0.9f32))
+        .stroke(BLACK)
+        .stroke_weight(1f32);
+    draw.xy(panel.xy())
+        .text(&text)
+        .w_h(panel.w() - PANEL_PADDING * 2f32, LINE_HEIGHT)
+        .font_size(FONT_SIZE)
+        .right_justify()
+        .no_line_wrap()
+        .color(BLACK);
+}
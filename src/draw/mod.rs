@@ -1,13 +1,20 @@
 mod draw_connection;
+mod draw_diagnostics;
 mod draw_legend;
 mod draw_room;
+mod draw_room_info;
+mod draw_route;
 
 use nannou::prelude::*;
 
 pub use draw_connection::draw_connections;
+pub use draw_diagnostics::draw_diagnostics;
 pub use draw_legend::draw_legend;
 pub use draw_room::draw_rooms;
+pub use draw_room_info::draw_room_info;
+pub use draw_route::{draw_route, draw_route_summary};
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct LabelColor {
     pub background: Rgb8,
     pub foreground: Rgb8,
@@ -1,15 +1,36 @@
+mod analysis;
 mod draw;
 mod model;
+mod options;
 mod parser;
 
-use crate::draw::{draw_connections, draw_legend, draw_rooms, LabelColor};
-use crate::model::{Connection, Direction, Exit, Model};
+use crate::analysis::{
+    analyze_graph, diff_areas, find_route, lint_area, reachability, report_json, vnum_report,
+};
+use crate::draw::{
+    draw_connections, draw_diagnostics, draw_legend, draw_room_info, draw_rooms, draw_route,
+    draw_route_summary, LabelColor,
+};
+use crate::model::{strip_color_codes, Connection, Direction, Exit, Model, RoomColoring, Vnum};
+use fnv::FnvHashMap;
 use nannou::event::ElementState;
 use nannou::prelude::*;
 use nannou::winit::event::DeviceEvent;
-use parser::{load_area, ParseRuleError, Rule};
+use options::{Format, Loaded, Options};
+use parser::Encoding;
+use std::error::Error;
 
 fn main() {
+    match std::env::args().nth(1).as_deref() {
+        Some("lint") => std::process::exit(lint(Options::from_args(std::env::args().skip(2)))),
+        Some("path") => std::process::exit(path(std::env::args().skip(2))),
+        Some("reach") => std::process::exit(reach(Options::from_args(std::env::args().skip(2)))),
+        Some("diff") => std::process::exit(diff(Options::from_args(std::env::args().skip(2)))),
+        Some("vnums") => std::process::exit(vnums(std::env::args().skip(2))),
+        Some("graph") => std::process::exit(graph(Options::from_args(std::env::args().skip(2)))),
+        _ => {}
+    }
+
     nannou::app(model)
         .event(event)
         .view(view)
@@ -17,38 +38,437 @@ fn main() {
         .run();
 }
 
-fn model(app: &App) -> Model {
-    let path = std::env::args().nth(1).unwrap_or_else(|| {
-        eprintln!("No path to area file supplied!");
-        std::process::exit(1);
-    });
-    let rules: Vec<_> = std::env::args()
-        .skip(2)
-        .filter_map(|arg| match Rule::try_from(arg.as_str()) {
-            Ok(rule) => Some(rule),
-            Err(ParseRuleError::UnknownRule) => None,
-            Err(e) => {
-                eprintln!("Error parsing {}, {:?}", &arg, e);
-                None
+/// Lints the areas without opening a window. Exits with 0 if nothing was found, 1 if anything
+/// was, and 2 if the areas couldn't be loaded at all.
+fn lint(options: Result<Options, String>) -> i32 {
+    let Loaded {
+        area,
+        diagnostics,
+        format,
+        ..
+    } = match load_headless(options) {
+        Some(loaded) => loaded,
+        None => return 2,
+    };
+    let lints = lint_area(&area);
+
+    match format {
+        Format::Json => println!("{}", report_json(&diagnostics, &lints)),
+        Format::Text => {
+            for diagnostic in &diagnostics {
+                println!("{diagnostic}");
             }
-        })
+            for lint in &lints {
+                println!("{lint}");
+            }
+        }
+    }
+    if diagnostics.is_empty() && lints.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Prints the cheapest way from one room to another, as the rooms along it and a speedwalk. Exits
+/// with 0 if there is a way, 1 if there isn't, and 2 if the areas couldn't be loaded.
+fn path(mut args: impl Iterator<Item = String>) -> i32 {
+    let ends = (args.next(), args.next());
+    let (from, to) = match ends {
+        (Some(from), Some(to)) => match (from.parse::<Vnum>(), to.parse::<Vnum>()) {
+            (Ok(from), Ok(to)) => (from, to),
+            _ => {
+                eprintln!("Usage: areview path FROM TO AREAFILE...");
+                return 2;
+            }
+        },
+        _ => {
+            eprintln!("Usage: areview path FROM TO AREAFILE...");
+            return 2;
+        }
+    };
+    let Loaded {
+        area,
+        diagnostics,
+        sector_table,
+        format,
+        traveler,
+        ..
+    } = match load_headless(Options::from_args(args)) {
+        Some(loaded) => loaded,
+        None => return 2,
+    };
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    for vnum in [from, to] {
+        if !area.all_rooms.contains_key(&vnum) {
+            eprintln!("There's no room {vnum} in the loaded areas");
+            return 2;
+        }
+    }
+
+    let room = |vnum| area.all_rooms.get(&vnum).map(|(room, _)| room.as_ref());
+    let route = match find_route(room, &sector_table, &traveler, from, to) {
+        Some(route) => route,
+        None => {
+            eprintln!("There's no way from room {from} to room {to}");
+            return 1;
+        }
+    };
+    match format {
+        Format::Json => {
+            let report = serde_json::json!({
+                "rooms": route.rooms,
+                "steps": route.steps,
+                "speedwalk": route.speedwalk(),
+                "cost": route.cost,
+            });
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+        Format::Text => {
+            for vnum in &route.rooms {
+                let name = &room(*vnum).unwrap().name;
+                println!(
+                    "{} {}",
+                    vnum,
+                    strip_color_codes(name, area.color_codes(*vnum))
+                );
+            }
+            println!("Speedwalk: {}", route.speedwalk());
+            println!("Movement cost: {}", route.cost);
+        }
+    }
+    0
+}
+
+/// Lists the rooms that can't be got to from the `entry=` rooms, and the ones that can't be left
+/// once got to. Exits with 0 if there are none, 1 if there are, and 2 if the areas couldn't be
+/// loaded.
+fn reach(options: Result<Options, String>) -> i32 {
+    let Loaded {
+        area,
+        diagnostics,
+        sector_table,
+        format,
+        traveler,
+        entries,
+        ..
+    } = match load_headless(options) {
+        Some(loaded) => loaded,
+        None => return 2,
+    };
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    if entries.is_empty() {
+        eprintln!("Name the rooms players come in through with entry=VNUM,VNUM...");
+        return 2;
+    }
+    for vnum in &entries {
+        if !area.all_rooms.contains_key(vnum) {
+            eprintln!("There's no room {vnum} in the loaded areas");
+            return 2;
+        }
+    }
+
+    let found = reachability(&area.all_rooms, &sector_table, &traveler, &entries);
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&found).unwrap()),
+        Format::Text => {
+            let name =
+                |vnum| strip_color_codes(&area.all_rooms[&vnum].0.name, area.color_codes(vnum));
+            for &vnum in &found.unreachable {
+                println!("unreachable: {} {}", vnum, name(vnum));
+            }
+            for &vnum in &found.traps {
+                println!("trap: {} {}", vnum, name(vnum));
+            }
+        }
+    }
+    if found.unreachable.is_empty() && found.traps.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Prints how the rooms hang together. Exits with 0 unless the areas couldn't be loaded.
+fn graph(options: Result<Options, String>) -> i32 {
+    let Loaded {
+        area,
+        diagnostics,
+        format,
+        ..
+    } = match load_headless(options) {
+        Some(loaded) => loaded,
+        None => return 2,
+    };
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let report = analyze_graph(&area.all_rooms);
+    if format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return 0;
+    }
+    let vnums = |vnums: &[Vnum]| {
+        let vnums: Vec<_> = vnums.iter().map(Vnum::to_string).collect();
+        vnums.join(" ")
+    };
+    println!("Rooms: {}", report.rooms);
+    println!("Exits per room: {:.2}", report.average_exits);
+    println!(
+        "Strongly connected components: {}, the biggest with {} rooms",
+        report.components.len(),
+        report.components[0].len()
+    );
+    for component in &report.components[1..] {
+        println!("  {}", vnums(component));
+    }
+    println!("Articulation rooms: {}", vnums(&report.articulation_rooms));
+    let bridges: Vec<_> = report
+        .bridges
+        .iter()
+        .map(|(a, b)| format!("{a}-{b}"))
         .collect();
+    println!("Bridges: {}", bridges.join(" "));
+    if let Some(diameter) = report.diameter {
+        println!(
+            "Diameter: {} steps, from {} to {}",
+            diameter.steps, diameter.from, diameter.to
+        );
+    }
+    println!("Dead-end chains: {}", report.dead_ends.len());
+    for chain in &report.dead_ends {
+        println!("  {}", vnums(chain));
+    }
+    0
+}
+
+/// Lists what changed between an old and a new version of the areas. Exits with 0 if nothing did,
+/// 1 if anything did, and 2 if either version couldn't be loaded.
+fn diff(options: Result<Options, String>) -> i32 {
+    let options = options.and_then(|mut options| {
+        if options.paths.len() != 2 {
+            return Err("Usage: areview diff OLD NEW".to_string());
+        }
+        options.compare = Some(options.paths.remove(0));
+        Ok(options)
+    });
+    let Loaded {
+        area,
+        diagnostics,
+        sector_table,
+        format,
+        previous,
+        ..
+    } = match load_headless(options) {
+        Some(loaded) => loaded,
+        None => return 2,
+    };
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let previous = previous.expect("compare= was set");
+    let changes = diff_areas(&previous, &area, &sector_table);
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&changes).unwrap()),
+        Format::Text => {
+            for change in &changes {
+                println!("{change}");
+            }
+        }
+    }
+    if changes.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Prints which vnums each area uses for rooms, mobiles and objects, and where there's room for
+/// `block=N` more. Exits with 0 unless the areas couldn't be loaded.
+fn vnums(args: impl Iterator<Item = String>) -> i32 {
+    let mut block = 10;
+    let mut rest = vec![];
+    for arg in args {
+        match arg.strip_prefix("block=") {
+            Some(size) => match size.parse() {
+                Ok(size) if size > 0 => block = size,
+                _ => {
+                    eprintln!("Invalid block size {size}");
+                    return 2;
+                }
+            },
+            None => rest.push(arg),
+        }
+    }
+    let Loaded {
+        area,
+        diagnostics,
+        format,
+        ..
+    } = match load_headless(Options::from_args(rest.into_iter())) {
+        Some(loaded) => loaded,
+        None => return 2,
+    };
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let report = vnum_report(&area, block);
+    if format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return 0;
+    }
+    let runs = |runs: &[(Vnum, Vnum)]| {
+        let runs: Vec<_> = runs
+            .iter()
+            .map(|&(first, last)| {
+                if first == last {
+                    first.to_string()
+                } else {
+                    format!("{first}-{last}")
+                }
+            })
+            .collect();
+        if runs.is_empty() {
+            "none".to_string()
+        } else {
+            runs.join(" ")
+        }
+    };
+    for area in &report {
+        println!("{}, vnums {}-{}", area.name, area.range.0, area.range.1);
+        for (kind, usage) in [
+            ("rooms", &area.rooms),
+            ("mobiles", &area.mobiles),
+            ("objects", &area.objects),
+        ] {
+            println!("  {}: {} used: {}", kind, usage.count, runs(&usage.used));
+            println!("    free: {}", runs(&usage.gaps));
+            match usage.next_block {
+                Some(first) => println!("    next {block} free: {first}-{}", first + block - 1),
+                None => println!("    no {block} free in a row"),
+            }
+        }
+    }
+    0
+}
 
-    let area = match load_area(&path, rules) {
-        Ok(area) => area,
+/// Loads the areas for a command that doesn't open a window, printing why if they can't be
+fn load_headless(options: Result<Options, String>) -> Option<Loaded> {
+    match options
+        .map_err(Box::<dyn Error>::from)
+        .and_then(Options::load)
+    {
+        Ok(loaded) => Some(loaded),
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    }
+}
+
+fn model(app: &App) -> Model {
+    let loaded = Options::from_args(std::env::args().skip(1))
+        .map_err(Box::<dyn Error>::from)
+        .and_then(Options::load);
+    let Loaded {
+        area,
+        diagnostics,
+        sector_table,
+        traveler,
+        entries,
+        coloring,
+        previous,
+        floors,
+        ..
+    } = match loaded {
+        Ok(loaded) => loaded,
         Err(e) => {
             eprintln!("{e}");
             std::process::exit(1);
         }
     };
 
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    let lints = lint_area(&area);
+    let entries: Vec<_> = entries
+        .into_iter()
+        .filter(|vnum| {
+            let known = area.all_rooms.contains_key(vnum);
+            if !known {
+                eprintln!("There's no room {vnum} in the loaded areas");
+            }
+            known
+        })
+        .collect();
+    // Without entrances there's nothing to reach anything from
+    let found = if entries.is_empty() {
+        Default::default()
+    } else {
+        reachability(&area.all_rooms, &sector_table, &traveler, &entries)
+    };
+
+    let mut coloring =
+        coloring.map(|metric| RoomColoring::new(metric, &analyze_graph(&area.all_rooms)));
+    let mut changes = FnvHashMap::default();
+    if let Some(previous) = previous {
+        // Showing what changed takes the place of any other coloring
+        let found = diff_areas(&previous, &area, &sector_table);
+        coloring = Some(RoomColoring::changes(&found));
+        for change in found {
+            changes
+                .entry(change.room())
+                .or_insert_with(Vec::new)
+                .push(change);
+        }
+    }
+
+    let title = match area.areas.as_slice() {
+        [area] if area.encoding == Encoding::Utf8 => {
+            format!(
+                "{} ({})",
+                strip_color_codes(&area.header.name, area.header.color_codes),
+                area.dialect
+            )
+        }
+        [area] => format!(
+            "{} ({}, {})",
+            strip_color_codes(&area.header.name, area.header.color_codes),
+            area.dialect,
+            area.encoding.name()
+        ),
+        areas => format!("{} areas", areas.len()),
+    };
     let _ = app
         .new_window()
-        .title("Avatar Area Visualizer")
+        .title(format!("{title} - Avatar Area Visualizer"))
         .build()
         .unwrap();
 
-    Model::new(30f32, area)
+    let window = app.window_rect();
+    let mut model = Model::new(
+        30f32,
+        window.w() / window.h(),
+        area,
+        floors,
+        sector_table,
+        diagnostics,
+        lints,
+    );
+    model.traveler = traveler;
+    model.coloring = coloring;
+    model.changes = changes;
+    model.unreachable = found.unreachable.into_iter().collect();
+    model.traps = found.traps.into_iter().collect();
+    model
 }
 
 fn apply_grab(model: &mut Model) {
@@ -84,22 +504,7 @@ fn event(app: &App, model: &mut Model, event: Event) {
             model.ui.last_click_device = Some(device_id);
             model.ui.last_click_time = app.duration.since_start;
 
-            let mut grabbed_room = None;
-            for (idx, &loc) in model.locations.iter().enumerate() {
-                let loc = if model.selected[idx] {
-                    loc + model.ui.grab_offset.unwrap_or(Vec2::default())
-                } else {
-                    loc
-                };
-                let half_square_size = model.square_size() * 0.5;
-                if app.mouse.x + half_square_size > loc.x
-                    && app.mouse.x - half_square_size < loc.x
-                    && app.mouse.y + half_square_size > loc.y
-                    && app.mouse.y - half_square_size < loc.y
-                {
-                    grabbed_room = Some(idx);
-                }
-            }
+            let grabbed_room = model.room_at(app.mouse.position());
             model.ui.grabbed = grabbed_room;
 
             // Handle selecting rooms
@@ -109,9 +514,12 @@ fn event(app: &App, model: &mut Model, event: Event) {
                     if model.selected[room_idx] {
                         apply_grab_to_room(model, room_idx);
                         model.selected[room_idx] = false;
+                        model.route = None;
                     } else {
                         apply_grab(model);
                         model.selected[room_idx] = true;
+                        // Selecting a second room shows the way to it from the first
+                        model.update_route(room_idx);
                     }
                     model.recalculate_guides();
                 } else {
@@ -121,12 +529,14 @@ fn event(app: &App, model: &mut Model, event: Event) {
                         apply_grab(model);
                         model.selected.fill(false);
                         model.selected[room_idx] = true;
+                        model.route = None;
                         model.recalculate_guides();
                     }
                 }
 
                 if is_double_click {
                     model.select_all_in_plane(model.room_planes[room_idx]);
+                    model.route = None;
                     model.recalculate_guides();
                 }
                 model.ui.grab_origin = Some(model.locations[room_idx]);
@@ -134,6 +544,7 @@ fn event(app: &App, model: &mut Model, event: Event) {
                 if !app.keys.mods.ctrl() {
                     apply_grab(model);
                     model.selected.fill(false);
+                    model.route = None;
                     model.clear_guides();
                 }
             }
@@ -194,7 +605,11 @@ fn view(app: &App, model: &Model, frame: Frame) {
     let draw = app.draw();
     draw.background().color(WHITE);
 
-    draw_legend(&draw.xy(app.window_rect().top_left()), &model.sectors);
+    draw_legend(
+        &draw.xy(app.window_rect().top_left()),
+        &model.areas,
+        &model.legend(),
+    );
 
     if app.keys.mods.shift() {
         draw_closest_guide(app, &draw, model, app.window_rect());
@@ -202,8 +617,20 @@ fn view(app: &App, model: &Model, frame: Frame) {
 
     draw_connections(&draw, model);
 
+    draw_route(&draw, model);
+
     draw_rooms(&draw, model);
 
+    draw_diagnostics(&draw, app.window_rect(), &model.diagnostics);
+
+    draw_route_summary(&draw, app.window_rect(), model);
+
+    if model.ui.grabbed.is_none() {
+        if let Some(idx) = model.room_at(app.mouse.position()) {
+            draw_room_info(&draw, app.window_rect(), model, idx);
+        }
+    }
+
     draw.to_frame(app, &frame).unwrap();
 }
 
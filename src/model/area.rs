@@ -0,0 +1,41 @@
+use crate::model::{strip_color_codes, ColorCodes, Vnum};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AreaHeader {
+    pub name: String,
+    pub builders: Option<String>,
+    pub levels: Option<(u32, u32)>,
+    pub vnums: Option<(Vnum, Vnum)>,
+    pub security: Option<u32>,
+    /// The color codes the area's strings are written with, which depend on its dialect
+    pub color_codes: ColorCodes,
+}
+
+impl AreaHeader {
+    /// Whether `vnum` lies inside the declared vnum range. Areas that don't declare one accept
+    /// anything.
+    pub fn contains_vnum(&self, vnum: Vnum) -> bool {
+        match self.vnums {
+            Some((low, high)) => low <= vnum && vnum <= high,
+            None => true,
+        }
+    }
+
+    /// One line per piece of header data the area actually declares
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![strip_color_codes(&self.name, self.color_codes)];
+        if let Some(builders) = &self.builders {
+            lines.push(format!("By {builders}"));
+        }
+        if let Some((low, high)) = self.levels {
+            lines.push(format!("Levels {low}-{high}"));
+        }
+        if let Some((low, high)) = self.vnums {
+            lines.push(format!("Vnums {low}-{high}"));
+        }
+        if let Some(security) = self.security {
+            lines.push(format!("Security {security}"));
+        }
+        lines
+    }
+}
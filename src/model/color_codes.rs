@@ -0,0 +1,325 @@
+use std::fmt::{Display, Formatter};
+
+/// The eight colors MUD clients know, each with a bright version
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Hue {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TextColor {
+    pub hue: Hue,
+    pub bright: bool,
+}
+
+impl TextColor {
+    const fn new(hue: Hue, bright: bool) -> Self {
+        TextColor { hue, bright }
+    }
+}
+
+/// Which color codes a dialect writes in its strings
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ColorCodes {
+    /// `{r`, as in ROM, Merc and AVATAR
+    #[default]
+    Rom,
+    /// `&R` for the foreground and `^b` for the background
+    Smaug,
+    /// No codes at all, every character is just that
+    Plain,
+}
+
+/// Something wrong with the color codes in a string, at a byte offset into it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorCodeProblem {
+    Unknown(String),
+    NotReset(String),
+}
+
+impl Display for ColorCodeProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorCodeProblem::Unknown(code) => write!(f, "Unknown color code `{code}`"),
+            ColorCodeProblem::NotReset(code) => {
+                write!(
+                    f,
+                    "Color code `{code}` isn't reset by the end of the string"
+                )
+            }
+        }
+    }
+}
+
+/// Text with its color codes taken out, along with the color of each character that's left.
+/// `None` is whatever color the text is drawn in by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColoredText {
+    pub text: String,
+    pub colors: Vec<Option<TextColor>>,
+}
+
+impl ColoredText {
+    /// Adds text that doesn't have any color codes in it
+    pub fn push_plain(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.colors.extend(text.chars().map(|_| None));
+    }
+
+    /// Adds a string from the area, in its colors. Colors it leaves on don't carry past its end.
+    pub fn push_coded(&mut self, text: &str, codes: ColorCodes) {
+        let mut color = None;
+        for (_, piece) in Pieces::new(text, codes) {
+            match piece {
+                Piece::Char(c) => {
+                    self.text.push(c);
+                    self.colors.push(color);
+                }
+                Piece::Foreground(foreground) => color = Some(foreground),
+                Piece::Reset => color = None,
+                Piece::Background | Piece::Style | Piece::Unknown(_) => {}
+            }
+        }
+    }
+}
+
+/// The text of a string with its color codes taken out, for measuring and searching
+pub fn strip_color_codes(text: &str, codes: ColorCodes) -> String {
+    Pieces::new(text, codes)
+        .filter_map(|(_, piece)| match piece {
+            Piece::Char(c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds codes that don't mean anything, and colors left on at the end of the string, which the
+/// MUD lets bleed into whatever gets sent next
+pub fn color_code_problems(text: &str, codes: ColorCodes) -> Vec<(usize, ColorCodeProblem)> {
+    let mut problems = vec![];
+    let mut left_on = None;
+    for (offset, piece) in Pieces::new(text, codes) {
+        match piece {
+            Piece::Foreground(_) | Piece::Background => left_on = Some(offset),
+            Piece::Reset => left_on = None,
+            Piece::Unknown(code) => {
+                problems.push((offset, ColorCodeProblem::Unknown(code.to_string())))
+            }
+            Piece::Char(_) | Piece::Style => {}
+        }
+    }
+    if let Some(offset) = left_on {
+        // Codes are always two ASCII characters
+        let code = &text[offset..offset + 2];
+        problems.push((offset, ColorCodeProblem::NotReset(code.to_string())));
+    }
+    problems
+}
+
+enum Piece<'a> {
+    Char(char),
+    Foreground(TextColor),
+    /// Backgrounds aren't drawn, but they still need resetting
+    Background,
+    Reset,
+    /// Blinking, underlines and other things that don't change the color
+    Style,
+    Unknown(&'a str),
+}
+
+/// Splits a string into characters and the `codes` it's written with. A code character followed
+/// by anything but a letter or symbol is just that character, and so are the other dialects'
+/// code characters.
+struct Pieces<'a> {
+    text: &'a str,
+    codes: ColorCodes,
+    pos: usize,
+}
+
+impl<'a> Pieces<'a> {
+    fn new(text: &'a str, codes: ColorCodes) -> Self {
+        Pieces {
+            text,
+            codes,
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Pieces<'a> {
+    type Item = (usize, Piece<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.pos;
+        let rest = &self.text[offset..];
+        let mut chars = rest.chars();
+        let first = chars.next()?;
+        let starts_code = match self.codes {
+            ColorCodes::Rom => first == '{',
+            ColorCodes::Smaug => first == '&' || first == '^',
+            ColorCodes::Plain => false,
+        };
+        let code = match chars.next() {
+            Some(second) if starts_code && second.is_ascii_graphic() => second,
+            _ => {
+                self.pos += first.len_utf8();
+                return Some((offset, Piece::Char(first)));
+            }
+        };
+        self.pos += 2;
+
+        let piece = match first {
+            '{' => rom_code(code),
+            '&' => smaug_code(code),
+            _ => background_code(code),
+        };
+        Some((offset, piece.unwrap_or(Piece::Unknown(&rest[..2]))))
+    }
+}
+
+fn rom_code(code: char) -> Option<Piece<'static>> {
+    let hue = match code.to_ascii_lowercase() {
+        'r' => Hue::Red,
+        'g' => Hue::Green,
+        'y' => Hue::Yellow,
+        'b' => Hue::Blue,
+        'm' => Hue::Magenta,
+        'c' => Hue::Cyan,
+        'w' => Hue::White,
+        'd' if code == 'D' => Hue::Black,
+        _ => {
+            return match code {
+                'x' => Some(Piece::Reset),
+                '{' => Some(Piece::Char('{')),
+                '/' => Some(Piece::Char('\n')),
+                '-' => Some(Piece::Char('~')),
+                '*' => Some(Piece::Style),
+                _ => None,
+            }
+        }
+    };
+    Some(Piece::Foreground(TextColor::new(
+        hue,
+        code.is_ascii_uppercase(),
+    )))
+}
+
+fn smaug_code(code: char) -> Option<Piece<'static>> {
+    match code {
+        '&' => Some(Piece::Char('&')),
+        'd' | 'D' => Some(Piece::Reset),
+        'i' | 'I' | 'u' | 'U' | 'v' | 'V' | 's' | 'S' => Some(Piece::Style),
+        _ => smaug_color(code).map(Piece::Foreground),
+    }
+}
+
+fn background_code(code: char) -> Option<Piece<'static>> {
+    match code {
+        '^' => Some(Piece::Char('^')),
+        _ => smaug_color(code).map(|_| Piece::Background),
+    }
+}
+
+fn smaug_color(code: char) -> Option<TextColor> {
+    let color = match code {
+        'x' => TextColor::new(Hue::Black, false),
+        'r' => TextColor::new(Hue::Red, false),
+        'g' => TextColor::new(Hue::Green, false),
+        'O' | 'o' | 'y' => TextColor::new(Hue::Yellow, false),
+        'b' => TextColor::new(Hue::Blue, false),
+        'p' => TextColor::new(Hue::Magenta, false),
+        'c' => TextColor::new(Hue::Cyan, false),
+        'w' => TextColor::new(Hue::White, false),
+        'z' => TextColor::new(Hue::Black, true),
+        'R' => TextColor::new(Hue::Red, true),
+        'G' => TextColor::new(Hue::Green, true),
+        'Y' => TextColor::new(Hue::Yellow, true),
+        'B' => TextColor::new(Hue::Blue, true),
+        'P' => TextColor::new(Hue::Magenta, true),
+        'C' => TextColor::new(Hue::Cyan, true),
+        'W' => TextColor::new(Hue::White, true),
+        _ => return None,
+    };
+    Some(color)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        color_code_problems, strip_color_codes, ColorCodeProblem, ColorCodes, ColoredText,
+    };
+    use super::{Hue, TextColor};
+
+    #[test]
+    fn strips_codes_of_each_dialect() {
+        use ColorCodes::*;
+        assert_eq!(strip_color_codes("{rThe {RTemple{x", Rom), "The Temple");
+        assert_eq!(
+            strip_color_codes("&RA ^bdark&d alley", Smaug),
+            "A dark alley"
+        );
+        assert_eq!(
+            strip_color_codes("Smith & Sons {{50%}", Rom),
+            "Smith & Sons {50%}"
+        );
+        assert_eq!(strip_color_codes("{rPlain{x", Plain), "{rPlain{x");
+    }
+
+    #[test]
+    fn leaves_other_dialects_codes_alone() {
+        assert_eq!(
+            strip_color_codes("R&D at AT&T", ColorCodes::Rom),
+            "R&D at AT&T"
+        );
+        assert!(color_code_problems("R&D at AT&T ^_^", ColorCodes::Rom).is_empty());
+        assert_eq!(
+            strip_color_codes("{rnot a code", ColorCodes::Smaug),
+            "{rnot a code"
+        );
+    }
+
+    #[test]
+    fn colors_characters_until_reset() {
+        let mut text = ColoredText::default();
+        text.push_plain("#1 ");
+        text.push_coded("{rre{Cd", ColorCodes::Rom);
+        text.push_plain("!");
+
+        assert_eq!(text.text, "#1 red!");
+        let red = Some(TextColor {
+            hue: Hue::Red,
+            bright: false,
+        });
+        let cyan = Some(TextColor {
+            hue: Hue::Cyan,
+            bright: true,
+        });
+        assert_eq!(text.colors, vec![None, None, None, red, red, cyan, None]);
+    }
+
+    #[test]
+    fn finds_unknown_and_unreset_codes() {
+        assert!(color_code_problems("{rThe Temple{x", ColorCodes::Rom).is_empty());
+        assert_eq!(
+            color_code_problems("The {qTemple {Rof doom", ColorCodes::Rom),
+            vec![
+                (4, ColorCodeProblem::Unknown("{q".to_string())),
+                (13, ColorCodeProblem::NotReset("{R".to_string())),
+            ]
+        );
+        assert_eq!(
+            color_code_problems("The &qTemple ^bof doom", ColorCodes::Smaug),
+            vec![
+                (4, ColorCodeProblem::Unknown("&q".to_string())),
+                (13, ColorCodeProblem::NotReset("^b".to_string())),
+            ]
+        );
+    }
+}
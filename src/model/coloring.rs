@@ -0,0 +1,125 @@
+use crate::analysis::{Change, GraphReport};
+use crate::model::Vnum;
+use crate::LabelColor;
+use fnv::FnvHashMap;
+use nannou::color::named::*;
+
+const COMPONENT_COLORS: &[nannou::color::Rgb8] = &[
+    LIGHTSKYBLUE,
+    PALEGREEN,
+    KHAKI,
+    PLUM,
+    LIGHTSALMON,
+    AQUAMARINE,
+    PINK,
+    WHEAT,
+];
+
+/// What rooms can be colored by instead of their sector
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    Components,
+    Chokepoints,
+    DeadEnds,
+}
+
+/// Colors that take the place of the rooms' sector colors, and what each of them means
+#[derive(Debug, Clone, Default)]
+pub struct RoomColoring {
+    pub legend: Vec<(String, LabelColor)>,
+    colors: FnvHashMap<Vnum, LabelColor>,
+}
+
+impl RoomColoring {
+    /// Rooms that don't stand out in whatever is measured
+    const OTHER: LabelColor = LabelColor {
+        background: GAINSBORO,
+        foreground: BLACK,
+    };
+
+    pub fn new(metric: Metric, report: &GraphReport) -> Self {
+        let mut coloring = RoomColoring::default();
+        match metric {
+            Metric::Components => {
+                // Only the first few colors fit in the legend, the rest are reused
+                let groups = report.components.iter().filter(|c| c.len() > 1);
+                for (group, &color) in groups.zip(COMPONENT_COLORS.iter().cycle()) {
+                    let color = LabelColor::light(color);
+                    if coloring.legend.len() < COMPONENT_COLORS.len() {
+                        coloring
+                            .legend
+                            .push((format!("{} rooms", group.len()), color));
+                    }
+                    coloring.paint(group, color);
+                }
+                coloring
+                    .legend
+                    .push(("On their own".to_string(), Self::OTHER));
+            }
+            Metric::Chokepoints => {
+                let bridge_ends: Vec<_> =
+                    report.bridges.iter().flat_map(|&(a, b)| [a, b]).collect();
+                let bridge_end = LabelColor::light(GOLD);
+                let articulation = LabelColor::dark(ORANGERED);
+                coloring.paint(&bridge_ends, bridge_end);
+                coloring.paint(&report.articulation_rooms, articulation);
+                coloring.legend = vec![
+                    ("Articulation room".to_string(), articulation),
+                    ("End of a bridge".to_string(), bridge_end),
+                    ("Other".to_string(), Self::OTHER),
+                ];
+            }
+            Metric::DeadEnds => {
+                let dead_end = LabelColor::dark(DARKRED);
+                let leading = LabelColor::light(LIGHTSALMON);
+                for chain in &report.dead_ends {
+                    coloring.paint(&chain[1..], leading);
+                    coloring.paint(&chain[..1], dead_end);
+                }
+                coloring.legend = vec![
+                    ("Dead end".to_string(), dead_end),
+                    ("Leads to a dead end".to_string(), leading),
+                    ("Other".to_string(), Self::OTHER),
+                ];
+            }
+        }
+        coloring
+    }
+
+    /// Colors the rooms that were added or changed since an older version of the area
+    pub fn changes(changes: &[Change]) -> Self {
+        let added = LabelColor::light(LIGHTGREEN);
+        let changed = LabelColor::light(GOLD);
+        let mut coloring = RoomColoring::default();
+        let mut removed = 0;
+        for change in changes {
+            match change {
+                Change::AddedRoom { room, .. } => coloring.paint(&[*room], added),
+                Change::RemovedRoom { .. } => removed += 1,
+                _ => coloring.paint(&[change.room()], changed),
+            }
+        }
+        coloring.legend = vec![
+            ("Added".to_string(), added),
+            ("Changed".to_string(), changed),
+            ("Unchanged".to_string(), Self::OTHER),
+        ];
+        // Removed rooms aren't on the map, so the legend is the only place to mention them
+        if removed > 0 {
+            coloring
+                .legend
+                .push((format!("{removed} removed"), LabelColor::dark(FIREBRICK)));
+        }
+        coloring
+    }
+
+    fn paint(&mut self, vnums: &[Vnum], color: LabelColor) {
+        for &vnum in vnums {
+            self.colors.insert(vnum, color);
+        }
+    }
+
+    pub fn color(&self, vnum: Vnum) -> LabelColor {
+        self.colors.get(&vnum).copied().unwrap_or(Self::OTHER)
+    }
+}
@@ -23,17 +23,17 @@ pub fn map_connection(
 ) -> Connection {
     match c {
         parser::Connection::OneWay { from, to, door } => Connection::OneWay {
-            from: map_exit(from, &all_rooms),
-            to: map_exit(to, &all_rooms),
+            from: map_exit(from, all_rooms),
+            to: map_exit(to, all_rooms),
             door,
         },
         parser::Connection::TwoWay { from, to, door } => Connection::TwoWay {
-            from: map_exit(from, &all_rooms),
-            to: map_exit(to, &all_rooms),
+            from: map_exit(from, all_rooms),
+            to: map_exit(to, all_rooms),
             door,
         },
         parser::Connection::External { from, to, door } => Connection::External {
-            from: map_exit(from, &all_rooms),
+            from: map_exit(from, all_rooms),
             to: to.to_string(),
             door,
         },
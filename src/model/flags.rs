@@ -0,0 +1,36 @@
+/// Parses a flag field, which may be a plain number (`1032`), numbers joined with pipes
+/// (`8|1024`), ROM-style letters (`DK`), or any mix of those.
+pub fn parse_flags(s: &str) -> Option<u32> {
+    let mut bits = 0;
+    for part in s.split('|') {
+        if part.is_empty() {
+            return None;
+        } else if part.bytes().all(|b| b.is_ascii_digit()) {
+            bits |= part.parse::<u32>().ok()?;
+        } else {
+            for letter in part.chars() {
+                bits |= match letter {
+                    'A'..='Z' => 1 << (letter as u32 - 'A' as u32),
+                    'a'..='f' => 1 << (letter as u32 - 'a' as u32 + 26),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(bits)
+}
+
+/// Writes flags the way ROM does, as letters, or `-` when none are set
+pub fn flag_letters(bits: u32) -> String {
+    let letters: String = ('A'..='Z')
+        .chain('a'..='f')
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .map(|(_, letter)| letter)
+        .collect();
+    if letters.is_empty() {
+        "-".to_string()
+    } else {
+        letters
+    }
+}
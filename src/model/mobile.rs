@@ -0,0 +1,47 @@
+use crate::model::Vnum;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mobile {
+    pub vnum: Vnum,
+    pub keywords: String,
+    pub short_description: String,
+    pub long_description: String,
+    pub act_flags: u32,
+    pub affect_flags: u32,
+    pub alignment: i32,
+    pub level: u32,
+    pub hitroll: i32,
+    pub armor: i32,
+    pub hit_dice: Dice,
+    pub damage_dice: Dice,
+}
+
+/// A dice expression such as `3d8+120`
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Dice {
+    pub number: u32,
+    pub size: u32,
+    pub bonus: i32,
+}
+
+impl Dice {
+    pub fn from_str(s: &str) -> Option<Dice> {
+        let (number, rest) = s.split_once(['d', 'D'])?;
+        let (size, bonus) = match rest.find(['+', '-']) {
+            Some(idx) => (&rest[..idx], rest[idx..].trim_start_matches('+')),
+            None => (rest, "0"),
+        };
+        Some(Dice {
+            number: number.parse().ok()?,
+            size: size.parse().ok()?,
+            bonus: bonus.parse().ok()?,
+        })
+    }
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d{}{:+}", self.number, self.size, self.bonus)
+    }
+}
@@ -1,39 +1,113 @@
+mod area;
+mod color_codes;
+mod coloring;
 mod connection;
+mod flags;
+mod mobile;
+mod object;
 mod position_rooms;
 mod room;
+mod sector_table;
+mod spawns;
 
-use crate::parser::ParsedArea;
+use crate::analysis::{find_route, Change, Lint, Route, Traveler};
+use crate::parser::{Diagnostic, ParsedArea};
+use crate::LabelColor;
+pub use area::AreaHeader;
+pub use color_codes::{
+    color_code_problems, strip_color_codes, ColorCodes, ColoredText, Hue, TextColor,
+};
+pub use coloring::{Metric, RoomColoring};
 pub use connection::{map_connection, Connection, Exit};
-use fnv::FnvHashSet;
-use nannou::prelude::{Rect, Vec2};
+pub use flags::{flag_letters, parse_flags};
+use fnv::{FnvHashMap, FnvHashSet};
+pub use mobile::{Dice, Mobile};
+use nannou::prelude::Vec2;
 use nannou::winit::event::DeviceId;
+pub use object::{affect_location_name, ItemType, Object, ObjectAffect};
 use position_rooms::position_rooms;
-pub use room::{Direction, Door, Room, Sector, Vnum};
+pub use position_rooms::FloorLayout;
+pub use room::{
+    Direction, Door, DoorState, ExtraDescription, Room, RoomExit, RoomFlag, RoomFlags, Sector, Vnum,
+};
+pub use sector_table::{Requirement, SectorTable};
+pub use spawns::{wear_location_name, RoomSpawns, SpawnedMobile, SpawnedObject};
 use std::time::Duration;
 
 #[derive(Debug, Default)]
 pub struct Model {
     square_size: f32,
+    pub areas: Vec<AreaHeader>,
     pub rooms: Vec<Room>,
+    /// Where each room is in `rooms`
+    pub room_indices: FnvHashMap<Vnum, usize>,
+    /// Which of `areas` each room belongs to
+    pub room_areas: Vec<usize>,
+    pub mobiles: FnvHashMap<Vnum, Mobile>,
+    pub objects: FnvHashMap<Vnum, Object>,
+    pub spawns: FnvHashMap<Vnum, RoomSpawns>,
     pub locations: Vec<Vec2>,
     pub room_planes: Vec<usize>,
     pub selected: Vec<bool>,
-    pub plane_areas: Vec<Rect>,
+    /// The sectors used by any of the rooms
     pub sectors: Vec<Sector>,
+    pub sector_table: SectorTable,
+    /// Colors rooms are drawn in instead of their sectors'
+    pub coloring: Option<RoomColoring>,
     pub connections: Vec<Connection>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Lint warnings, by the room they're about
+    pub lints: FnvHashMap<Vnum, Vec<Lint>>,
+    /// What's different from the version of the areas they were compared with, by room
+    pub changes: FnvHashMap<Vnum, Vec<Change>>,
+    /// Rooms that can't be got to from the entrances named on the command line
+    pub unreachable: FnvHashSet<Vnum>,
+    /// Rooms that can be got to from the entrances, but not back out of
+    pub traps: FnvHashSet<Vnum>,
+    /// Who routes between rooms are found for
+    pub traveler: Traveler,
+    /// The way between the two selected rooms
+    pub route: Option<SelectedRoute>,
     pub ui: Ui,
 }
 
+/// A route from the room selected first to the one selected after it
+#[derive(Debug)]
+pub struct SelectedRoute {
+    pub from: usize,
+    pub to: usize,
+    /// `None` if there's no way
+    pub route: Option<Route>,
+}
+
 impl Model {
     pub fn new(
         square_size: f32,
+        aspect_ratio: f32,
         ParsedArea {
+            areas,
+            room_areas,
             all_rooms,
+            mobiles,
+            objects,
+            spawns,
             grouped_rooms,
             connections,
+            stacking,
+            ..
         }: ParsedArea,
+        floors: FloorLayout,
+        sector_table: SectorTable,
+        diagnostics: Vec<Diagnostic>,
+        lints: Vec<Lint>,
     ) -> Self {
-        let (plane_areas, all_locations) = position_rooms(grouped_rooms, square_size);
+        let all_locations = position_rooms(
+            grouped_rooms,
+            &stacking.floors,
+            floors,
+            square_size,
+            aspect_ratio,
+        );
 
         let num_rooms = all_locations.len();
 
@@ -44,6 +118,12 @@ impl Model {
             .into_iter()
             .collect();
         let rooms: Vec<_> = all_locations.iter().map(|l| (*l.room).clone()).collect();
+        let room_areas = rooms.iter().map(|r| room_areas[&r.vnum]).collect();
+        let room_indices = rooms
+            .iter()
+            .enumerate()
+            .map(|(idx, room)| (room.vnum, idx))
+            .collect();
         let locations = all_locations.iter().map(|l| Vec2::new(l.x, l.y)).collect();
         let room_planes = all_locations.into_iter().map(|l| l.group).collect();
 
@@ -54,13 +134,26 @@ impl Model {
 
         Model {
             square_size,
+            areas: areas.into_iter().map(|a| a.header).collect(),
             rooms,
+            room_indices,
+            room_areas,
+            mobiles: mobiles.into_iter().map(|m| (m.vnum, m)).collect(),
+            objects: objects.into_iter().map(|o| (o.vnum, o)).collect(),
+            spawns,
             locations,
             room_planes,
             selected: vec![false; num_rooms],
-            plane_areas,
             sectors,
+            sector_table,
             connections,
+            diagnostics,
+            lints: lints
+                .into_iter()
+                .fold(FnvHashMap::default(), |mut lints, lint| {
+                    lints.entry(lint.room).or_insert_with(Vec::new).push(lint);
+                    lints
+                }),
             ..Default::default()
         }
     }
@@ -70,6 +163,54 @@ impl Model {
         self.square_size
     }
 
+    /// The colors a room is drawn in
+    pub fn room_color(&self, room: &Room) -> LabelColor {
+        match &self.coloring {
+            Some(coloring) => coloring.color(room.vnum),
+            None => self.sector_table.style(room.sector).color,
+        }
+    }
+
+    /// What each of the colors rooms are drawn in means
+    pub fn legend(&self) -> Vec<(String, LabelColor)> {
+        match &self.coloring {
+            Some(coloring) => coloring.legend.clone(),
+            None => self
+                .sector_table
+                .styles()
+                .iter()
+                .filter(|style| self.sectors.contains(&style.sector))
+                .map(|style| (style.name.clone(), style.color))
+                .collect(),
+        }
+    }
+
+    /// Where a room is drawn, taking an in-progress drag into account
+    pub fn location_of(&self, idx: usize) -> Vec2 {
+        if self.selected[idx] {
+            self.locations[idx] + self.ui.grab_offset.unwrap_or_default()
+        } else {
+            self.locations[idx]
+        }
+    }
+
+    /// Index of the room whose square contains `point`, taking an in-progress drag into account
+    pub fn room_at(&self, point: Vec2) -> Option<usize> {
+        let half_square_size = self.square_size * 0.5;
+        let mut found = None;
+        for idx in 0..self.locations.len() {
+            let loc = self.location_of(idx);
+            if point.x + half_square_size > loc.x
+                && point.x - half_square_size < loc.x
+                && point.y + half_square_size > loc.y
+                && point.y - half_square_size < loc.y
+            {
+                found = Some(idx);
+            }
+        }
+        found
+    }
+
     pub fn select_all_in_plane(&mut self, group: usize) {
         for (&plane, selected) in self.room_planes.iter().zip(&mut self.selected) {
             if plane == group {
@@ -78,6 +219,32 @@ impl Model {
         }
     }
 
+    /// Finds the route to a room that was just selected, if it and one other room are all that's
+    /// selected
+    pub fn update_route(&mut self, newest: usize) {
+        let selected: Vec<_> = (0..self.rooms.len())
+            .filter(|&idx| self.selected[idx])
+            .collect();
+        self.route = match selected.as_slice() {
+            &[a, b] if a == newest || b == newest => {
+                let from = if a == newest { b } else { a };
+                let route = find_route(
+                    |vnum| self.room_indices.get(&vnum).map(|&idx| &self.rooms[idx]),
+                    &self.sector_table,
+                    &self.traveler,
+                    self.rooms[from].vnum,
+                    self.rooms[newest].vnum,
+                );
+                Some(SelectedRoute {
+                    from,
+                    to: newest,
+                    route,
+                })
+            }
+            _ => None,
+        };
+    }
+
     pub fn recalculate_guides(&mut self) {
         let locations: Vec<_> = self
             .locations
@@ -0,0 +1,182 @@
+use crate::model::{ExtraDescription, Vnum};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub vnum: Vnum,
+    pub keywords: String,
+    pub short_description: String,
+    pub long_description: String,
+    pub item_type: ItemType,
+    pub extra_flags: u32,
+    pub wear_flags: u32,
+    /// v0 through v4. Their meaning depends on the item type, and some dialects write words
+    /// (weapon classes, spell names) instead of numbers, so they're kept as written.
+    pub values: [String; 5],
+    pub weight: i32,
+    pub cost: i32,
+    pub affects: Vec<ObjectAffect>,
+    pub extra_descriptions: Vec<ExtraDescription>,
+}
+
+impl Object {
+    pub fn value(&self, idx: usize) -> Option<i32> {
+        self.values.get(idx)?.parse().ok()
+    }
+
+    /// The vnum of the key that unlocks this container
+    pub fn container_key(&self) -> Option<Vnum> {
+        match self.item_type {
+            ItemType::Container => self.vnum_value(2),
+            _ => None,
+        }
+    }
+
+    /// The room a portal leads to
+    pub fn portal_destination(&self) -> Option<Vnum> {
+        match self.item_type {
+            ItemType::Portal => self.vnum_value(3),
+            _ => None,
+        }
+    }
+
+    fn vnum_value(&self, idx: usize) -> Option<Vnum> {
+        // Zero and negative values mean "none"
+        self.value(idx).filter(|v| *v > 0).map(|v| v as Vnum)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ObjectAffect {
+    pub location: i32,
+    pub modifier: i32,
+}
+
+/// Affect locations as numbered by Merc's `APPLY_` constants
+pub fn affect_location_name(location: i32) -> &'static str {
+    match location {
+        1 => "strength",
+        2 => "dexterity",
+        3 => "intelligence",
+        4 => "wisdom",
+        5 => "constitution",
+        6 => "sex",
+        7 => "class",
+        8 => "level",
+        9 => "age",
+        10 => "height",
+        11 => "weight",
+        12 => "mana",
+        13 => "hit points",
+        14 => "moves",
+        15 => "gold",
+        16 => "experience",
+        17 => "armor class",
+        18 => "hitroll",
+        19 => "damroll",
+        20..=24 => "saving throws",
+        _ => "nothing",
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ItemType {
+    Light,
+    Scroll,
+    Wand,
+    Staff,
+    Weapon,
+    Treasure,
+    Armor,
+    Potion,
+    Clothing,
+    Furniture,
+    Trash,
+    Container,
+    DrinkContainer,
+    Key,
+    Food,
+    Money,
+    Boat,
+    NpcCorpse,
+    PcCorpse,
+    Fountain,
+    Pill,
+    Map,
+    Portal,
+    Other(u32),
+}
+
+impl ItemType {
+    /// Item types are numbers in Merc-style areas and words in ROM-style ones
+    pub fn from_str(s: &str) -> Option<ItemType> {
+        use ItemType::*;
+        let item_type = match s.to_ascii_lowercase().as_str() {
+            "1" | "light" => Light,
+            "2" | "scroll" => Scroll,
+            "3" | "wand" => Wand,
+            "4" | "staff" => Staff,
+            "5" | "weapon" => Weapon,
+            "8" | "treasure" => Treasure,
+            "9" | "armor" => Armor,
+            "10" | "potion" => Potion,
+            "11" | "clothing" => Clothing,
+            "12" | "furniture" => Furniture,
+            "13" | "trash" => Trash,
+            "15" | "container" => Container,
+            "17" | "drink" => DrinkContainer,
+            "18" | "key" => Key,
+            "19" | "food" => Food,
+            "20" | "money" => Money,
+            "22" | "boat" => Boat,
+            "23" | "npc_corpse" => NpcCorpse,
+            "24" | "pc_corpse" => PcCorpse,
+            "25" | "fountain" => Fountain,
+            "26" | "pill" => Pill,
+            "28" | "map" => Map,
+            "29" | "portal" => Portal,
+            // ROM types with nothing in particular to resolve
+            "protect" => Other(27),
+            "warp_stone" => Other(30),
+            "room_key" => Other(31),
+            "gem" => Other(32),
+            "jewelry" => Other(33),
+            "jukebox" => Other(34),
+            other => Other(other.parse().ok()?),
+        };
+        Some(item_type)
+    }
+}
+
+impl Display for ItemType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ItemType::*;
+        let name = match self {
+            Light => "light",
+            Scroll => "scroll",
+            Wand => "wand",
+            Staff => "staff",
+            Weapon => "weapon",
+            Treasure => "treasure",
+            Armor => "armor",
+            Potion => "potion",
+            Clothing => "clothing",
+            Furniture => "furniture",
+            Trash => "trash",
+            Container => "container",
+            DrinkContainer => "drink container",
+            Key => "key",
+            Food => "food",
+            Money => "money",
+            Boat => "boat",
+            NpcCorpse => "NPC corpse",
+            PcCorpse => "PC corpse",
+            Fountain => "fountain",
+            Pill => "pill",
+            Map => "map",
+            Portal => "portal",
+            Other(number) => return write!(f, "item type {number}"),
+        };
+        f.write_str(name)
+    }
+}
@@ -1,5 +1,8 @@
 use crate::model::Room;
+use crate::parser::Floor;
+use fnv::FnvHashMap;
 use nannou::prelude::{Rect, Vec2};
+use std::cmp::Reverse;
 use std::rc::Rc;
 
 #[derive(Debug, Clone)]
@@ -10,40 +13,131 @@ pub struct Location {
     pub group: usize,
 }
 
+/// Space left between planes, in squares
+const PLANE_GAP: f32 = 2f32;
+
+/// How planes joined by up and down exits are drawn
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FloorLayout {
+    /// Each plane on its own, as small as it'll go
+    #[default]
+    Packed,
+    /// The floors of a stack drawn the same size, with the rooms at either end of the stairs
+    /// between them in the same spot, like a building's blueprints
+    Blueprint,
+}
+
+/// Positions the rooms of each plane, and packs the planes into rows so they don't overlap and
+/// roughly fill a window of `aspect_ratio`. Planes that are stacked on top of each other are kept
+/// together, top floor first. With the blueprint layout, planes on the same floor of a stack are
+/// drawn together.
 pub fn position_rooms(
     planes: Vec<Vec<crate::parser::Location>>,
+    floors: &[Floor],
+    layout: FloorLayout,
     square_size: f32,
-) -> (Vec<Rect>, Vec<Location>) {
+    aspect_ratio: f32,
+) -> Vec<Location> {
     let mut grouped_locations: Vec<_> = planes
         .into_iter()
         .enumerate()
         .map(|(index, plane)| position_rooms_in_plane(plane, square_size, index))
         .collect();
 
-    let shift_groups_by = grouped_locations.len() as isize / 2isize;
-    for (i, (plane, locations)) in grouped_locations.iter_mut().enumerate() {
-        // temporarily arrange the groups of rooms
-        let mut pos = i as f32 - shift_groups_by as f32;
-        pos *= 150f32;
+    // Where each plane's rooms are on its floor, and the floor plan of each stack
+    let shifts: Vec<_> = floors
+        .iter()
+        .map(|floor| match layout {
+            FloorLayout::Packed => Vec2::default(),
+            FloorLayout::Blueprint => {
+                Vec2::new(floor.offset.0 as f32, floor.offset.1 as f32) * square_size * 2.0
+            }
+        })
+        .collect();
+    let mut stack_areas: FnvHashMap<usize, Rect> = FnvHashMap::default();
+    for ((plane, _), (floor, &shift)) in grouped_locations.iter().zip(floors.iter().zip(&shifts)) {
+        let area = plane.shift(shift);
+        stack_areas
+            .entry(floor.stack)
+            .and_modify(|stack| {
+                *stack = stack
+                    .stretch_to(area.bottom_left())
+                    .stretch_to(area.top_right())
+            })
+            .or_insert(area);
+    }
 
-        let (x, y) = plane.x_y();
-        *plane = Rect::from_xy_wh(Vec2::new(pos, pos), plane.wh() + square_size);
+    // Each plane is drawn on its own, or on its floor along with the others there
+    let frame_key = |idx: usize| {
+        let floor = floors[idx];
+        let own = match layout {
+            FloorLayout::Packed => idx,
+            FloorLayout::Blueprint => 0,
+        };
+        (floor.stack, Reverse(floor.level), own)
+    };
+    let mut frames: Vec<_> = (0..floors.len()).map(frame_key).collect();
+    frames.sort_unstable();
+    frames.dedup();
+    let frame_areas: Vec<_> = frames
+        .iter()
+        .map(|&(stack, _, own)| match layout {
+            FloorLayout::Packed => grouped_locations[own].0,
+            FloorLayout::Blueprint => stack_areas[&stack],
+        })
+        .collect();
 
+    let sizes: Vec<_> = frame_areas
+        .iter()
+        .map(|area| area.wh() + square_size)
+        .collect();
+    let centers = pack_planes(&sizes, square_size * PLANE_GAP, aspect_ratio);
+    for (idx, (_, locations)) in grouped_locations.iter_mut().enumerate() {
+        let frame = frames.binary_search(&frame_key(idx)).unwrap();
+        let shift = centers[frame] - frame_areas[frame].xy() + shifts[idx];
         for loc in locations {
-            loc.x += pos - x;
-            loc.y += pos - y;
+            loc.x += shift.x;
+            loc.y += shift.y;
         }
     }
 
-    let plane_areas: Vec<_> = grouped_locations.iter().map(|(area, _)| *area).collect();
-
     let mut all_locations: Vec<_> = grouped_locations
         .into_iter()
-        .map(|(_, ls)| ls)
-        .flatten()
+        .flat_map(|(_, ls)| ls)
         .collect();
-    all_locations.sort_by(|a, b| a.room.vnum.cmp(&b.room.vnum));
-    (plane_areas, all_locations)
+    all_locations.sort_by_key(|l| l.room.vnum);
+    all_locations
+}
+
+/// Centers for planes of `sizes`, put in rows in that order and centered on the origin, with `gap`
+/// between them. Rows are made about as wide as they need to be for the whole to have
+/// `aspect_ratio`.
+fn pack_planes(sizes: &[Vec2], gap: f32, aspect_ratio: f32) -> Vec<Vec2> {
+    let area: f32 = sizes
+        .iter()
+        .map(|size| (size.x + gap) * (size.y + gap))
+        .sum();
+    let widest = sizes.iter().fold(0f32, |widest, size| widest.max(size.x));
+    let row_width = (area * aspect_ratio).sqrt().max(widest);
+
+    let mut centers = vec![Vec2::default(); sizes.len()];
+    let (mut x, mut y, mut row_height, mut width) = (0f32, 0f32, 0f32, 0f32);
+    for (idx, &size) in sizes.iter().enumerate() {
+        // Start a new row once a plane would stick out more than halfway past the end
+        if x > 0f32 && x + size.x * 0.5 > row_width {
+            x = 0f32;
+            y -= row_height + gap;
+            row_height = 0f32;
+        }
+        centers[idx] = Vec2::new(x + size.x * 0.5, y - size.y * 0.5);
+        x += size.x + gap;
+        row_height = row_height.max(size.y);
+        width = width.max(x - gap);
+    }
+
+    let height = row_height - y;
+    let shift = Vec2::new(-width * 0.5, height * 0.5);
+    centers.iter().map(|&center| center + shift).collect()
 }
 
 fn position_rooms_in_plane(
@@ -77,3 +171,118 @@ fn position_rooms_in_plane(
 
     (area, locations)
 }
+
+#[cfg(test)]
+mod test {
+    use super::{pack_planes, position_rooms, FloorLayout};
+    use crate::model::Direction;
+    use crate::parser::{stack_planes, Location};
+    use nannou::prelude::{Rect, Vec2};
+
+    /// The box around the centers of each plane's rooms
+    fn plane_bounds(locations: &[super::Location], planes: usize) -> Vec<Rect> {
+        (0..planes)
+            .map(|plane| {
+                let mut found = locations.iter().filter(|loc| loc.group == plane);
+                let first = found.next().unwrap();
+                let start = Rect::from_xy_wh(Vec2::new(first.x, first.y), Vec2::ZERO);
+                found.fold(start, |bounds, loc| {
+                    bounds.stretch_to(Vec2::new(loc.x, loc.y))
+                })
+            })
+            .collect()
+    }
+
+    fn assert_apart(rects: &[Rect]) {
+        for (idx, rect) in rects.iter().enumerate() {
+            for other in &rects[idx + 1..] {
+                assert!(
+                    rect.overlap(*other).is_none(),
+                    "{rect:?} overlaps {other:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lines_up_floors_in_blueprint_layout() {
+        use Direction::*;
+        let planes = vec![
+            vec![
+                Location::test(1, (0, 0), &[(East, 2)]),
+                Location::test(2, (1, 0), &[(West, 1), (Up, 3)]),
+            ],
+            vec![
+                Location::test(3, (0, 0), &[(Down, 2), (North, 4)]),
+                Location::test(4, (0, 1), &[(South, 3)]),
+            ],
+            vec![Location::test(5, (0, 0), &[])],
+        ];
+        let floors = stack_planes(&planes).floors;
+        let locations = position_rooms(planes, &floors, FloorLayout::Blueprint, 10f32, 1f32);
+
+        assert_apart(&plane_bounds(&locations, 3));
+        // Both floors of the stack are drawn the same size and lined up, the top one first, so
+        // room 3 is straight across from room 2 on the floor before it
+        let (stairs_down, stairs_up) = (&locations[1], &locations[2]);
+        let across = Vec2::new(stairs_up.x - stairs_down.x, stairs_up.y - stairs_down.y);
+        assert!(across.y == 0f32 && across.x < 0f32 || across.x == 0f32 && across.y > 0f32);
+    }
+
+    #[test]
+    fn keeps_stacked_floors_together_in_packed_layout() {
+        use Direction::*;
+        // The ground floor and the floor above are the first and last planes, with a plane that
+        // has nothing to do with them in between
+        let planes = vec![
+            vec![
+                Location::test(1, (0, 0), &[(East, 2)]),
+                Location::test(2, (1, 0), &[(West, 1), (Up, 5)]),
+            ],
+            vec![
+                Location::test(3, (0, 0), &[(North, 4)]),
+                Location::test(4, (0, 1), &[(South, 3)]),
+            ],
+            vec![Location::test(5, (0, 0), &[(Down, 2)])],
+        ];
+        let floors = stack_planes(&planes).floors;
+        let locations = position_rooms(planes, &floors, FloorLayout::Packed, 10f32, 1f32);
+
+        let areas = plane_bounds(&locations, 3);
+        assert_apart(&areas);
+        // Read row by row, the upper floor comes right before the ground floor
+        let mut order: Vec<_> = (0..areas.len()).collect();
+        order.sort_by(|&a, &b| {
+            (-areas[a].top(), areas[a].left())
+                .partial_cmp(&(-areas[b].top(), areas[b].left()))
+                .unwrap()
+        });
+        let upper = order.iter().position(|&plane| plane == 2).unwrap();
+        assert_eq!(order[upper + 1], 0);
+    }
+
+    #[test]
+    fn packs_planes_without_overlap() {
+        let sizes = [
+            Vec2::new(300f32, 200f32),
+            Vec2::new(60f32, 60f32),
+            Vec2::new(120f32, 240f32),
+            Vec2::new(60f32, 60f32),
+            Vec2::new(180f32, 60f32),
+        ];
+        let centers = pack_planes(&sizes, 10f32, 16f32 / 9f32);
+        let rects: Vec<_> = centers
+            .iter()
+            .zip(&sizes)
+            .map(|(&center, &size)| Rect::from_xy_wh(center, size))
+            .collect();
+
+        assert_apart(&rects);
+        let all = rects.iter().fold(rects[0], |all, rect| {
+            all.stretch_to(rect.top_right())
+                .stretch_to(rect.bottom_left())
+        });
+        assert!(all.x().abs() < 0.01 && all.y().abs() < 0.01);
+        assert!(all.w() > all.h());
+    }
+}
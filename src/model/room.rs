@@ -1,8 +1,9 @@
-use crate::LabelColor;
+use crate::model::flags::parse_flags;
 use fnv::FnvHashMap;
-use nannou::color::named::*;
+use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     North,
     East,
@@ -10,9 +11,53 @@ pub enum Direction {
     West,
     Up,
     Down,
+    Northeast,
+    Northwest,
+    Southeast,
+    Southwest,
+    In,
+    Out,
 }
 
 impl Direction {
+    pub fn from_str(s: &str) -> Option<Direction> {
+        use Direction::*;
+        match s {
+            "0" => Some(North),
+            "1" => Some(East),
+            "2" => Some(South),
+            "3" => Some(West),
+            "4" => Some(Up),
+            "5" => Some(Down),
+            "6" => Some(Northeast),
+            "7" => Some(Northwest),
+            "8" => Some(Southeast),
+            "9" => Some(Southwest),
+            "10" => Some(In),
+            "11" => Some(Out),
+            _ => None,
+        }
+    }
+
+    /// What players type to walk this way
+    pub fn command(&self) -> &'static str {
+        use Direction::*;
+        match self {
+            North => "n",
+            East => "e",
+            South => "s",
+            West => "w",
+            Up => "u",
+            Down => "d",
+            Northeast => "ne",
+            Northwest => "nw",
+            Southeast => "se",
+            Southwest => "sw",
+            In => "in",
+            Out => "out",
+        }
+    }
+
     pub fn opposite(&self) -> Direction {
         use Direction::*;
         match self {
@@ -22,21 +67,141 @@ impl Direction {
             West => East,
             Up => Down,
             Down => Up,
+            Northeast => Southwest,
+            Northwest => Southeast,
+            Southeast => Northwest,
+            Southwest => Northeast,
+            In => Out,
+            Out => In,
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum Door {
-    None,
-    Closed,
+/// Lock flags on one side of an exit
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Door {
+    pub is_door: bool,
+    pub closed: bool,
+    pub locked: bool,
+    pub pickproof: bool,
+    pub secret: bool,
 }
 
 impl Door {
+    pub const NONE: Door = Door {
+        is_door: false,
+        closed: false,
+        locked: false,
+        pickproof: false,
+        secret: false,
+    };
+
+    /// Merc's exit locks field, where 1 is a door and 2 a pickproof door. ROM adds 3 and 4 for
+    /// doors that can't be passed through, which look the same on a map. Anything bigger is read as
+    /// `EX_` bits, since some descendants write those instead.
+    pub fn from_locks(locks: u32) -> Door {
+        let door = Door {
+            is_door: true,
+            ..Door::NONE
+        };
+        match locks {
+            0 => Door::NONE,
+            1 | 3 => door,
+            2 | 4 => Door {
+                pickproof: true,
+                ..door
+            },
+            _ => Door::from_bits(locks),
+        }
+    }
+
+    /// `EX_` bits as SMAUG writes them to area files
+    pub fn from_bits(bits: u32) -> Door {
+        Door {
+            is_door: bits & 1 != 0,
+            closed: bits & 2 != 0,
+            locked: bits & 4 != 0,
+            secret: bits & 8 != 0,
+            pickproof: bits & 32 != 0,
+        }
+    }
+
+    /// The door after a `D` reset has put it in `state`
+    pub fn reset_to(&self, state: DoorState) -> Door {
+        Door {
+            closed: state != DoorState::Open,
+            locked: state == DoorState::Locked,
+            ..*self
+        }
+    }
+
     pub fn or(&self, other: &Door) -> Door {
-        match (self, other) {
-            (Door::None, Door::None) => Door::None,
-            _ => Door::Closed,
+        Door {
+            is_door: self.is_door || other.is_door,
+            closed: self.closed || other.closed,
+            locked: self.locked || other.locked,
+            pickproof: self.pickproof || other.pickproof,
+            secret: self.secret || other.secret,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut words = vec![];
+        if self.secret {
+            words.push("secret");
+        }
+        if self.pickproof {
+            words.push("pickproof");
+        }
+        words.push(if self.locked {
+            "locked"
+        } else if self.closed {
+            "closed"
+        } else {
+            "open"
+        });
+        words.push("door");
+        words.join(" ")
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DoorState {
+    Open,
+    Closed,
+    Locked,
+}
+
+impl DoorState {
+    /// Door state as set by a `D` reset
+    pub fn from_reset_state(state: &str) -> Option<DoorState> {
+        match state {
+            "0" => Some(DoorState::Open),
+            "1" => Some(DoorState::Closed),
+            "2" => Some(DoorState::Locked),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomExit {
+    pub destination: Vnum,
+    pub door: Door,
+    pub keywords: String,
+    pub description: String,
+    pub key: Option<Vnum>,
+}
+
+impl RoomExit {
+    #[cfg(test)]
+    pub fn new(destination: Vnum, door: Door) -> Self {
+        RoomExit {
+            destination,
+            door,
+            keywords: String::new(),
+            description: String::new(),
+            key: None,
         }
     }
 }
@@ -48,10 +213,123 @@ pub struct Room {
     pub name: String,
     pub vnum: Vnum,
     pub string_vnum: String,
-    pub exits: FnvHashMap<Direction, (Vnum, Door)>,
+    pub description: String,
+    pub flags: RoomFlags,
+    pub extra_descriptions: Vec<ExtraDescription>,
+    pub exits: FnvHashMap<Direction, RoomExit>,
     pub sector: Sector,
-    // pub safe: boolean,
-    // pub cursed: boolean,
+}
+
+impl Room {
+    /// A room with nothing but open exits, as the tests need them
+    #[cfg(test)]
+    pub fn test(vnum: Vnum, exits: &[(Direction, Vnum)]) -> Self {
+        Room {
+            name: String::new(),
+            vnum,
+            string_vnum: vnum.to_string(),
+            description: String::new(),
+            flags: RoomFlags::default(),
+            extra_descriptions: vec![],
+            exits: exits
+                .iter()
+                .map(|&(direction, destination)| {
+                    (direction, RoomExit::new(destination, Door::NONE))
+                })
+                .collect(),
+            sector: Sector::Inside,
+        }
+    }
+
+    /// Puts `door` on the exit that way
+    #[cfg(test)]
+    pub fn with_door(mut self, direction: Direction, door: Door) -> Self {
+        self.exits.get_mut(&direction).unwrap().door = door;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraDescription {
+    pub keywords: String,
+    pub description: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RoomFlag {
+    Dark,
+    Death,
+    NoMob,
+    Indoors,
+    Private,
+    Safe,
+    Solitary,
+    PetShop,
+    NoRecall,
+}
+
+impl RoomFlag {
+    pub const ALL: &[RoomFlag] = &[
+        RoomFlag::Dark,
+        RoomFlag::Death,
+        RoomFlag::NoMob,
+        RoomFlag::Indoors,
+        RoomFlag::Private,
+        RoomFlag::Safe,
+        RoomFlag::Solitary,
+        RoomFlag::PetShop,
+        RoomFlag::NoRecall,
+    ];
+
+    /// Bit values as used by Merc and most of its descendants
+    pub fn bit(&self) -> u32 {
+        use RoomFlag::*;
+        match self {
+            Dark => 1,
+            Death => 2,
+            NoMob => 4,
+            Indoors => 8,
+            Private => 512,
+            Safe => 1024,
+            Solitary => 2048,
+            PetShop => 4096,
+            NoRecall => 8192,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        use RoomFlag::*;
+        match self {
+            Dark => "dark",
+            Death => "death trap",
+            NoMob => "no mob",
+            Indoors => "indoors",
+            Private => "private",
+            Safe => "safe",
+            Solitary => "solitary",
+            PetShop => "pet shop",
+            NoRecall => "no recall",
+        }
+    }
+}
+
+/// Raw room flag bits. Bits that don't correspond to a known [RoomFlag] are kept so nothing is
+/// lost, they just can't be named.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct RoomFlags(pub u32);
+
+impl RoomFlags {
+    pub fn from_str(s: &str) -> Option<RoomFlags> {
+        parse_flags(s).map(RoomFlags)
+    }
+
+    pub fn contains(&self, flag: RoomFlag) -> bool {
+        self.0 & flag.bit() != 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RoomFlag> + '_ {
+        RoomFlag::ALL.iter().copied().filter(|f| self.contains(*f))
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -70,12 +348,17 @@ pub enum Sector {
     Underwater,
     OnBottom,
     RogueGuild,
+    Underground,
+    Lava,
+    Swamp,
+    /// A sector number given its own name and color in the config file
+    Custom(u32),
 }
 
 impl Sector {
-    pub fn from_str(s: &str) -> Sector {
+    pub fn from_str(s: &str) -> Option<Sector> {
         use Sector::*;
-        match s {
+        let sector = match s {
             "0" => Inside,
             "1" => City,
             "2" => Field,
@@ -90,29 +373,8 @@ impl Sector {
             "11" => Underwater,
             "12" => OnBottom,
             "13" => RogueGuild,
-            _ => Inside,
-        }
-    }
-
-    pub fn color(&self) -> LabelColor {
-        match self {
-            Sector::Inside => LabelColor::light(GAINSBORO),
-            Sector::House => LabelColor::light(BISQUE),
-            Sector::City => LabelColor::light(DARKGRAY),
-            Sector::RogueGuild => LabelColor::dark(DARKSLATEGRAY),
-
-            Sector::Field => LabelColor::light(LIGHTGREEN),
-            Sector::Hills => LabelColor::light(MEDIUMSEAGREEN),
-            Sector::Forest => LabelColor::dark(SEAGREEN),
-            Sector::Mountain => LabelColor::dark(OLIVEDRAB),
-            Sector::Desert => LabelColor::dark(OLIVE),
-
-            Sector::WaterSwim => LabelColor::light(SKYBLUE),
-            Sector::WaterNoswim => LabelColor::light(DEEPSKYBLUE),
-            Sector::Underwater => LabelColor::dark(ROYALBLUE),
-            Sector::OnBottom => LabelColor::dark(MEDIUMBLUE),
-
-            Sector::Air => LabelColor::light(ALICEBLUE),
-        }
+            _ => return None,
+        };
+        Some(sector)
     }
 }
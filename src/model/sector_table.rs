@@ -0,0 +1,261 @@
+use crate::model::Sector;
+use crate::LabelColor;
+use fnv::FnvHashMap;
+use nannou::color::named::*;
+use nannou::color::Rgb8;
+use serde::Deserialize;
+use std::error::Error;
+use std::path::Path;
+
+/// Something a character needs to enter rooms of a sector
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Requirement {
+    Boat,
+    Flight,
+}
+
+/// What a sector is called in the legend, the colors its rooms are drawn in, and what it takes to
+/// move through it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorStyle {
+    pub sector: Sector,
+    pub name: String,
+    pub color: LabelColor,
+    /// Movement points it costs to walk through, as in ROM's `movement_loss` table
+    pub move_cost: u32,
+    pub needs: Option<Requirement>,
+}
+
+/// The sectors areview knows about, in the order the legend lists them. Starts out with the
+/// built in sectors, and a config file can add sector numbers of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorTable {
+    styles: Vec<SectorStyle>,
+    /// Sector numbers from the config file, which are read before the dialect gets a look in
+    numbers: FnvHashMap<String, Sector>,
+}
+
+impl Default for SectorTable {
+    fn default() -> Self {
+        use Requirement::*;
+        use Sector::*;
+        let builtin = [
+            (Inside, "Inside", LabelColor::light(GAINSBORO), 1, None),
+            (House, "House", LabelColor::light(BISQUE), 1, None),
+            (City, "City", LabelColor::light(DARKGRAY), 2, None),
+            (
+                RogueGuild,
+                "Rogue Guild",
+                LabelColor::dark(DARKSLATEGRAY),
+                1,
+                None,
+            ),
+            (Field, "Field", LabelColor::light(LIGHTGREEN), 2, None),
+            (Hills, "Hills", LabelColor::light(MEDIUMSEAGREEN), 4, None),
+            (Forest, "Forest", LabelColor::dark(SEAGREEN), 3, None),
+            (Mountain, "Mountain", LabelColor::dark(OLIVEDRAB), 6, None),
+            (Desert, "Desert", LabelColor::dark(OLIVE), 6, None),
+            (
+                WaterSwim,
+                "Water (swim)",
+                LabelColor::light(SKYBLUE),
+                4,
+                None,
+            ),
+            (
+                WaterNoswim,
+                "Water (no swim)",
+                LabelColor::light(DEEPSKYBLUE),
+                1,
+                Some(Boat),
+            ),
+            (
+                Underwater,
+                "Underwater",
+                LabelColor::dark(ROYALBLUE),
+                6,
+                None,
+            ),
+            (OnBottom, "On Bottom", LabelColor::dark(MEDIUMBLUE), 6, None),
+            (Swamp, "Swamp", LabelColor::dark(DARKOLIVEGREEN), 4, None),
+            (
+                Underground,
+                "Underground",
+                LabelColor::dark(SADDLEBROWN),
+                2,
+                None,
+            ),
+            (Lava, "Lava", LabelColor::dark(ORANGERED), 6, None),
+            (Air, "Air", LabelColor::light(ALICEBLUE), 10, Some(Flight)),
+        ];
+        SectorTable {
+            styles: builtin
+                .into_iter()
+                .map(|(sector, name, color, move_cost, needs)| SectorStyle {
+                    sector,
+                    name: name.to_string(),
+                    color,
+                    move_cost,
+                    needs,
+                })
+                .collect(),
+            numbers: FnvHashMap::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default)]
+    sector: Vec<SectorConfig>,
+}
+
+#[derive(Deserialize)]
+struct SectorConfig {
+    number: u32,
+    name: String,
+    color: String,
+    /// `black` or `white`, or left out to pick whichever stands out against `color`
+    text: Option<String>,
+    #[serde(default = "default_move_cost")]
+    cost: u32,
+    /// `boat` or `flight`
+    needs: Option<String>,
+}
+
+fn default_move_cost() -> u32 {
+    1
+}
+
+impl SectorTable {
+    /// Reads the `[[sector]]` entries of a config file on top of the built in sectors
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_config(&text)
+    }
+
+    fn from_config(text: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Config = toml::from_str(text)?;
+        let mut table = SectorTable::default();
+        for entry in config.sector {
+            let background = parse_color(&entry.color).ok_or_else(|| {
+                format!(
+                    "Invalid color `{}` for sector {}",
+                    entry.color, entry.number
+                )
+            })?;
+            let color = match entry.text.as_deref() {
+                Some("black") => LabelColor::light(background),
+                Some("white") => LabelColor::dark(background),
+                Some(text) => {
+                    return Err(format!(
+                        "Invalid text color `{}` for sector {}, expected black or white",
+                        text, entry.number
+                    )
+                    .into())
+                }
+                None if luma(background) > 0.5 => LabelColor::light(background),
+                None => LabelColor::dark(background),
+            };
+
+            let needs = match entry.needs.as_deref() {
+                None => None,
+                Some("boat") => Some(Requirement::Boat),
+                Some("flight") => Some(Requirement::Flight),
+                Some(needs) => {
+                    return Err(format!(
+                        "Invalid need `{}` for sector {}, expected boat or flight",
+                        needs, entry.number
+                    )
+                    .into())
+                }
+            };
+
+            let sector = Sector::Custom(entry.number);
+            table.styles.retain(|style| style.sector != sector);
+            table.styles.push(SectorStyle {
+                sector,
+                name: entry.name,
+                color,
+                move_cost: entry.cost,
+                needs,
+            });
+            table.numbers.insert(entry.number.to_string(), sector);
+        }
+        Ok(table)
+    }
+
+    /// The sector a number was given in the config file, if it was
+    pub fn sector(&self, number: &str) -> Option<Sector> {
+        self.numbers.get(number).copied()
+    }
+
+    pub fn style(&self, sector: Sector) -> &SectorStyle {
+        self.styles
+            .iter()
+            .find(|style| style.sector == sector)
+            .unwrap_or(&self.styles[0])
+    }
+
+    pub fn styles(&self) -> &[SectorStyle] {
+        &self.styles
+    }
+}
+
+/// Reads a `#rrggbb` color
+fn parse_color(color: &str) -> Option<Rgb8> {
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(nannou::color::rgb_u32(rgb))
+}
+
+fn luma(color: Rgb8) -> f32 {
+    (0.299 * color.red as f32 + 0.587 * color.green as f32 + 0.114 * color.blue as f32) / 255.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Requirement, SectorTable};
+    use crate::model::Sector;
+    use crate::LabelColor;
+    use nannou::color::named::*;
+
+    #[test]
+    fn adds_sectors_from_config() {
+        let table = SectorTable::from_config(
+            r##"
+[[sector]]
+number = 16
+name = "Astral Plane"
+color = "#191970"
+cost = 8
+needs = "flight"
+
+[[sector]]
+number = 13
+name = "Guild Hall"
+color = "#f0e68c"
+"##,
+        )
+        .unwrap();
+
+        assert_eq!(table.sector("16"), Some(Sector::Custom(16)));
+        assert_eq!(table.sector("3"), None);
+        let astral = table.style(Sector::Custom(16));
+        assert_eq!(astral.name, "Astral Plane");
+        assert_eq!(astral.color, LabelColor::dark(MIDNIGHTBLUE));
+        assert_eq!(astral.move_cost, 8);
+        assert_eq!(astral.needs, Some(Requirement::Flight));
+        assert_eq!(table.style(Sector::Custom(13)).move_cost, 1);
+        assert_eq!(
+            table.style(Sector::Custom(13)).color,
+            LabelColor::light(KHAKI)
+        );
+        assert_eq!(table.style(Sector::Forest).name, "Forest");
+
+        assert!(
+            SectorTable::from_config("[[sector]]\nnumber = 1\nname = \"x\"\ncolor = \"nope\"")
+                .is_err()
+        );
+    }
+}
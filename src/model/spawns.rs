@@ -0,0 +1,106 @@
+use crate::model::Vnum;
+
+/// Everything a zone reset puts in one room
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomSpawns {
+    pub mobiles: Vec<SpawnedMobile>,
+    pub objects: Vec<SpawnedObject>,
+    /// Set by an `R` reset, which shuffles this many of the room's exits
+    pub randomized_exits: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnedMobile {
+    pub vnum: Vnum,
+    pub inventory: Vec<SpawnedObject>,
+    pub equipment: Vec<(i32, SpawnedObject)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnedObject {
+    pub vnum: Vnum,
+    pub contents: Vec<SpawnedObject>,
+}
+
+impl RoomSpawns {
+    /// Adds what another area's resets put in the same room
+    pub fn merge(&mut self, other: RoomSpawns) {
+        self.mobiles.extend(other.mobiles);
+        self.objects.extend(other.objects);
+        self.randomized_exits = other.randomized_exits.or(self.randomized_exits);
+    }
+}
+
+impl SpawnedMobile {
+    pub fn new(vnum: Vnum) -> Self {
+        SpawnedMobile {
+            vnum,
+            inventory: vec![],
+            equipment: vec![],
+        }
+    }
+}
+
+impl SpawnedObject {
+    pub fn new(vnum: Vnum) -> Self {
+        SpawnedObject {
+            vnum,
+            contents: vec![],
+        }
+    }
+}
+
+impl RoomSpawns {
+    /// The most recently spawned object with the given vnum, wherever it is in the room. This is
+    /// the object a `P` reset puts things into.
+    pub fn find_object_mut(&mut self, vnum: Vnum) -> Option<&mut SpawnedObject> {
+        if let Some(found) = find_in(&mut self.objects, vnum) {
+            return Some(found);
+        }
+        for mobile in self.mobiles.iter_mut().rev() {
+            if let Some(found) = find_in(&mut mobile.inventory, vnum) {
+                return Some(found);
+            }
+            for (_, object) in mobile.equipment.iter_mut().rev() {
+                if let Some(found) = find_in(std::slice::from_mut(object), vnum) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+}
+
+fn find_in(objects: &mut [SpawnedObject], vnum: Vnum) -> Option<&mut SpawnedObject> {
+    for object in objects.iter_mut().rev() {
+        if object.vnum == vnum {
+            return Some(object);
+        }
+        if let Some(found) = find_in(&mut object.contents, vnum) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Wear locations as numbered by Merc's `E` resets
+pub fn wear_location_name(location: i32) -> &'static str {
+    match location {
+        0 => "used as light",
+        1 | 2 => "worn on finger",
+        3 | 4 => "worn around neck",
+        5 => "worn on body",
+        6 => "worn on head",
+        7 => "worn on legs",
+        8 => "worn on feet",
+        9 => "worn on hands",
+        10 => "worn on arms",
+        11 => "worn as shield",
+        12 => "worn about body",
+        13 => "worn about waist",
+        14 | 15 => "worn around wrist",
+        16 => "wielded",
+        17 => "held",
+        _ => "equipped",
+    }
+}
@@ -0,0 +1,250 @@
+use crate::analysis::{DoorPolicy, Traveler};
+use crate::model::{FloorLayout, Metric, SectorTable, Vnum};
+use crate::parser::{
+    dialect_by_name, encoding_by_name, load_area, AreaDialect, CollisionFix, Diagnostic, Encoding,
+    Layout, ParseRuleError, ParsedArea, Rule, DIALECT_NAMES, ENCODING_NAMES,
+};
+use std::error::Error;
+use std::path::PathBuf;
+
+const CONFIG_FILE: &str = "areview.toml";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// The areas, along with what the command line said to do with them
+pub struct Loaded {
+    pub area: ParsedArea,
+    pub diagnostics: Vec<Diagnostic>,
+    pub sector_table: SectorTable,
+    pub format: Format,
+    pub traveler: Traveler,
+    pub entries: Vec<Vnum>,
+    pub coloring: Option<Metric>,
+    /// The version of the areas named with `compare=`
+    pub previous: Option<ParsedArea>,
+    pub floors: FloorLayout,
+}
+
+/// What was asked for on the command line. Options are all `name=value`, anything else is an
+/// area file or a list of them.
+pub struct Options {
+    pub paths: Vec<PathBuf>,
+    pub layout: Layout,
+    pub dialect: Option<Box<dyn AreaDialect>>,
+    pub encoding: Option<Encoding>,
+    pub config: Option<PathBuf>,
+    pub format: Format,
+    pub traveler: Traveler,
+    /// Rooms players come into the areas through
+    pub entries: Vec<Vnum>,
+    /// What to color rooms by, if not their sector
+    pub coloring: Option<Metric>,
+    /// An older version of the areas to compare them with
+    pub compare: Option<PathBuf>,
+    /// How planes joined by up and down exits are drawn
+    pub floors: FloorLayout,
+}
+
+impl Options {
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            paths: vec![],
+            layout: Layout::default(),
+            dialect: None,
+            encoding: None,
+            config: None,
+            format: Format::Text,
+            traveler: Traveler::default(),
+            entries: vec![],
+            coloring: None,
+            compare: None,
+            floors: FloorLayout::default(),
+        };
+        for arg in args {
+            if let Some(name) = arg.strip_prefix("dialect=") {
+                options.dialect = Some(dialect_by_name(name).ok_or_else(|| {
+                    format!(
+                        "Unknown dialect {}, expected one of {}",
+                        name,
+                        DIALECT_NAMES.join(", ")
+                    )
+                })?);
+                continue;
+            }
+            if let Some(name) = arg.strip_prefix("encoding=") {
+                options.encoding = Some(encoding_by_name(name).ok_or_else(|| {
+                    format!(
+                        "Unknown encoding {}, expected one of {}",
+                        name,
+                        ENCODING_NAMES.join(", ")
+                    )
+                })?);
+                continue;
+            }
+            if let Some(path) = arg.strip_prefix("compare=") {
+                options.compare = Some(PathBuf::from(path));
+                continue;
+            }
+            if let Some(path) = arg.strip_prefix("config=") {
+                options.config = Some(PathBuf::from(path));
+                continue;
+            }
+            if let Some(format) = arg.strip_prefix("format=") {
+                options.format = match format {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err(format!("Unknown format {format}, expected text or json")),
+                };
+                continue;
+            }
+            if let Some(metric) = arg.strip_prefix("color=") {
+                options.coloring = match metric {
+                    "sector" => None,
+                    "components" => Some(Metric::Components),
+                    "chokepoints" => Some(Metric::Chokepoints),
+                    "dead-ends" => Some(Metric::DeadEnds),
+                    _ => {
+                        return Err(format!(
+                            "Unknown coloring {metric}, expected sector, components, chokepoints \
+                             or dead-ends"
+                        ))
+                    }
+                };
+                continue;
+            }
+            if let Some(entries) = arg.strip_prefix("entry=") {
+                for entry in entries.split(',') {
+                    let vnum = entry
+                        .parse()
+                        .map_err(|_| format!("Invalid entry vnum {entry}"))?;
+                    options.entries.push(vnum);
+                }
+                continue;
+            }
+            if let Some(lacking) = arg.strip_prefix("without=") {
+                for lack in lacking.split(',') {
+                    match lack {
+                        "boat" => options.traveler.boat = false,
+                        "flight" => options.traveler.flight = false,
+                        _ => {
+                            return Err(format!("Unknown ability {lack}, expected boat or flight"))
+                        }
+                    }
+                }
+                continue;
+            }
+            if let Some(doors) = arg.strip_prefix("doors=") {
+                options.traveler.doors = match doors {
+                    "any" => DoorPolicy::Any,
+                    "unlocked" => DoorPolicy::Unlocked,
+                    "open" => DoorPolicy::Open,
+                    _ => {
+                        return Err(format!(
+                            "Unknown door policy {doors}, expected any, unlocked or open"
+                        ))
+                    }
+                };
+                continue;
+            }
+            if let Some(one_way) = arg.strip_prefix("one-way=") {
+                options.traveler.one_way_exits = match one_way {
+                    "yes" => true,
+                    "no" => false,
+                    _ => return Err(format!("Expected one-way=yes or one-way=no, not {arg}")),
+                };
+                continue;
+            }
+            if let Some(fix) = arg.strip_prefix("collisions=") {
+                options.layout.collisions = match fix {
+                    "report" => CollisionFix::Report,
+                    "split" => CollisionFix::Split,
+                    "stretch" => CollisionFix::Stretch,
+                    _ => {
+                        return Err(format!(
+                            "Unknown collision fix {fix}, expected report, split or stretch"
+                        ))
+                    }
+                };
+                continue;
+            }
+            if let Some(floors) = arg.strip_prefix("floors=") {
+                options.floors = match floors {
+                    "packed" => FloorLayout::Packed,
+                    "blueprint" => FloorLayout::Blueprint,
+                    _ => {
+                        return Err(format!(
+                            "Unknown floor layout {floors}, expected packed or blueprint"
+                        ))
+                    }
+                };
+                continue;
+            }
+            if !arg.contains('=') {
+                options.paths.push(PathBuf::from(arg));
+                continue;
+            }
+            match Rule::try_from(arg.as_str()) {
+                Ok(rule) => options.layout.rules.push(rule),
+                Err(ParseRuleError::UnknownRule) => {}
+                Err(e) => eprintln!("Error parsing {}, {:?}", &arg, e),
+            }
+        }
+        if options.paths.is_empty() {
+            return Err("No path to area file supplied!".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Reads the config file and loads the areas
+    pub fn load(self) -> Result<Loaded, Box<dyn Error>> {
+        // Without a config file named, one in the current directory is used if it's there
+        let config = self.config.or_else(|| {
+            let default = PathBuf::from(CONFIG_FILE);
+            default.exists().then_some(default)
+        });
+        let sector_table = match &config {
+            Some(path) => {
+                SectorTable::load(path).map_err(|e| format!("{}: {}", path.display(), e))?
+            }
+            None => SectorTable::default(),
+        };
+
+        let (area, diagnostics) = load_area(
+            &self.paths,
+            &self.layout,
+            self.dialect.as_deref(),
+            self.encoding,
+            &sector_table,
+        )?;
+        // Whatever was wrong with the old version doesn't matter any more
+        let previous = match &self.compare {
+            Some(path) => Some(
+                load_area(
+                    std::slice::from_ref(path),
+                    &self.layout,
+                    self.dialect.as_deref(),
+                    self.encoding,
+                    &sector_table,
+                )?
+                .0,
+            ),
+            None => None,
+        };
+
+        Ok(Loaded {
+            area,
+            diagnostics,
+            sector_table,
+            format: self.format,
+            traveler: self.traveler,
+            entries: self.entries,
+            coloring: self.coloring,
+            previous,
+            floors: self.floors,
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Replaces any `.lst` files among `paths` with the area files they list
+pub fn expand_area_lists(paths: &[PathBuf]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut expanded = vec![];
+    for path in paths {
+        if matches!(path.extension(), Some(ext) if ext.eq_ignore_ascii_case("lst")) {
+            let text =
+                std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let dir = path.parent().unwrap_or_else(|| Path::new(""));
+            expanded.extend(read_area_list(&text).into_iter().map(|file| dir.join(file)));
+        } else {
+            expanded.push(path.clone());
+        }
+    }
+    Ok(expanded)
+}
+
+/// An `area.lst` names one file per line, relative to itself, and ends with a `$`
+fn read_area_list(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .take_while(|line| *line != "$")
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_area_list;
+
+    #[test]
+    fn reads_files_until_dollar() {
+        let list = "limbo.are\nmidgaard.are\n\n  school.are\n$\nignored.are\n";
+        assert_eq!(
+            read_area_list(list),
+            vec!["limbo.are", "midgaard.are", "school.are"]
+        );
+    }
+}
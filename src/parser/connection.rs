@@ -1,4 +1,5 @@
 use crate::model::{Direction, Door, Room, Vnum};
+use fnv::{FnvHashMap, FnvHashSet};
 use std::rc::Rc;
 
 pub enum Connection {
@@ -15,38 +16,37 @@ pub struct Exit {
 
 pub fn find_connections(rooms: &[Rc<Room>]) -> Vec<Connection> {
     let mut connections: Vec<Connection> = Vec::new();
+    // Whole worlds have too many exits to search the connections found so far for each one
+    let rooms_by_vnum: FnvHashMap<Vnum, &Rc<Room>> = rooms.iter().map(|r| (r.vnum, r)).collect();
+    let mut connected: FnvHashSet<(Vnum, Direction)> = FnvHashSet::default();
 
     for room in rooms {
-        for (&direction, (destination, from_door)) in &room.exits {
+        for (&direction, from_exit) in &room.exits {
+            let from_door = &from_exit.door;
             let exit = Exit {
                 direction,
                 in_room: room.vnum,
             };
-            if connections.iter().any(|conn| match conn {
-                Connection::TwoWay { from, .. } if *from == exit => true,
-                Connection::TwoWay { to, .. } if *to == exit => true,
-                Connection::OneWay { from, .. } if *from == exit => true,
-                Connection::External { from, .. } if *from == exit => true,
-                _ => false,
-            }) {
+            if !connected.insert((room.vnum, direction)) {
                 continue;
             }
-            let conn = if let Some(dest) = rooms.iter().find(|r| r.vnum == *destination) {
+            let conn = if let Some(dest) = rooms_by_vnum.get(&from_exit.destination) {
                 let matching_exit = dest
                     .exits
                     .iter()
-                    .find(|(dir, (vnum, _))| *vnum == room.vnum && **dir == direction.opposite());
+                    .find(|(dir, e)| e.destination == room.vnum && **dir == direction.opposite());
                 let matching_exit_in_another_dir =
-                    dest.exits.iter().find(|(_, (vnum, _))| *vnum == room.vnum);
+                    dest.exits.iter().find(|(_, e)| e.destination == room.vnum);
 
-                if let Some((&dir, (_, to_door))) = matching_exit.or(matching_exit_in_another_dir) {
+                if let Some((&dir, to_exit)) = matching_exit.or(matching_exit_in_another_dir) {
+                    connected.insert((dest.vnum, dir));
                     Connection::TwoWay {
                         from: exit,
                         to: Exit {
                             direction: dir,
                             in_room: dest.vnum,
                         },
-                        door: from_door.or(to_door),
+                        door: from_door.or(&to_exit.door),
                     }
                 } else {
                     Connection::OneWay {
@@ -61,7 +61,7 @@ pub fn find_connections(rooms: &[Rc<Room>]) -> Vec<Connection> {
             } else {
                 Connection::External {
                     from: exit,
-                    to: *destination,
+                    to: from_exit.destination,
                     door: *from_door,
                 }
             };
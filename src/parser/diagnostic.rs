@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found while reading an area file. Lines and columns count from 1, and are 0 when
+/// the problem isn't tied to any particular place in the file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}: {}", self.file, self.severity, self.message)
+        } else {
+            write!(
+                f,
+                "{}:{}:{}: {}: {}",
+                self.file, self.line, self.column, self.severity, self.message
+            )
+        }
+    }
+}
+
+/// Collects the diagnostics for one file. The parsers only ever hand around slices of the file's
+/// text, so a diagnostic's position is worked out from where its slice starts.
+pub struct Diagnostics<'a> {
+    file: String,
+    text: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Diagnostics<'a> {
+    pub fn new(file: impl Into<String>, text: &'a str) -> Self {
+        Diagnostics {
+            file: file.into(),
+            text,
+            diagnostics: vec![],
+        }
+    }
+
+    pub fn warning(&mut self, at: &str, message: impl Display) {
+        self.push(Severity::Warning, Some(at), message);
+    }
+
+    /// A warning at `at`, or about the file as a whole when there's no place in it to point to
+    pub fn warning_at(&mut self, at: Option<&str>, message: impl Display) {
+        self.push(Severity::Warning, at, message);
+    }
+
+    pub fn error(&mut self, at: &str, message: impl Display) {
+        self.push(Severity::Error, Some(at), message);
+    }
+
+    fn push(&mut self, severity: Severity, at: Option<&str>, message: impl Display) {
+        let (line, column) = at.and_then(|at| self.position_of(at)).unwrap_or_default();
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: self.file.clone(),
+            line,
+            column,
+            message: message.to_string(),
+        });
+    }
+
+    /// Finds where `at` starts in the file, if it's a slice of it at all
+    fn position_of(&self, at: &str) -> Option<(usize, usize)> {
+        let offset = (at.as_ptr() as usize).checked_sub(self.text.as_ptr() as usize)?;
+        let before = self.text.get(..offset)?;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count() + 1;
+        Some((line, column))
+    }
+
+    /// The diagnostics in the order they appear in the file
+    pub fn into_vec(mut self) -> Vec<Diagnostic> {
+        self.diagnostics.sort_by_key(|d| (d.line, d.column));
+        self.diagnostics
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Diagnostics, Severity};
+
+    #[test]
+    fn finds_line_and_column_of_slices() {
+        let text = "#ROOMS\n#1000\nThe Temple~\n";
+        let mut diagnostics = Diagnostics::new("temple.are", text);
+        diagnostics.error(&text[13..], "Invalid room");
+        diagnostics.warning_at(None, "Somewhere else");
+
+        // Sorted by position, with the ones that have none first
+        let diagnostics = diagnostics.into_vec();
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "temple.are: warning: Somewhere else"
+        );
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+        assert_eq!((diagnostics[1].line, diagnostics[1].column), (3, 1));
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "temple.are:3:1: error: Invalid room"
+        );
+    }
+}
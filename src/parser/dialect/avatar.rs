@@ -0,0 +1,11 @@
+use crate::parser::dialect::AreaDialect;
+
+/// The dialect used by [AVATAR](https://www.outland.org), which areview was written against. It
+/// reads like Merc, with pipe-separated flag fields and a few extra sectors.
+pub struct Avatar;
+
+impl AreaDialect for Avatar {
+    fn name(&self) -> &'static str {
+        "AVATAR"
+    }
+}
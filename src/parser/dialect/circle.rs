@@ -0,0 +1,67 @@
+use crate::model::{AreaHeader, ColorCodes, Direction, Mobile, Object, Room, Sector, SectorTable};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::dialect::AreaDialect;
+use crate::parser::parse_resets::Reset;
+use crate::parser::parse_wld::parse_wld;
+use std::error::Error;
+
+/// CircleMUD and tbaMUD world (`.wld`) files. Mobiles, objects and zone resets live in separate
+/// files, so only the rooms are read.
+pub struct Circle;
+
+impl AreaDialect for Circle {
+    fn name(&self) -> &'static str {
+        "CircleMUD"
+    }
+
+    fn color_codes(&self) -> ColorCodes {
+        ColorCodes::Plain
+    }
+
+    fn sector(&self, number: &str) -> Option<Sector> {
+        match number {
+            "8" => Some(Sector::Air),
+            "9" => Some(Sector::Underwater),
+            "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" => Sector::from_str(number),
+            _ => None,
+        }
+    }
+
+    /// tbaMUD puts northwest before northeast
+    fn direction(&self, number: &str) -> Option<Direction> {
+        match number {
+            "6" => Some(Direction::Northwest),
+            "7" => Some(Direction::Northeast),
+            _ => Direction::from_str(number),
+        }
+    }
+
+    fn parse_header(&self, _text: &str) -> Option<AreaHeader> {
+        None
+    }
+
+    fn parse_rooms(
+        &self,
+        text: &str,
+        sectors: &SectorTable,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Vec<Room>, Box<dyn Error>> {
+        parse_wld(text, self, sectors, diagnostics)
+    }
+
+    fn parse_mobiles(&self, _text: &str, _diagnostics: &mut Diagnostics) -> Vec<Mobile> {
+        vec![]
+    }
+
+    fn parse_objects(&self, _text: &str, _diagnostics: &mut Diagnostics) -> Vec<Object> {
+        vec![]
+    }
+
+    fn parse_resets<'a>(
+        &self,
+        _text: &'a str,
+        _diagnostics: &mut Diagnostics,
+    ) -> Vec<(&'a str, Reset)> {
+        vec![]
+    }
+}
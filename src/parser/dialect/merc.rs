@@ -0,0 +1,22 @@
+use crate::model::Sector;
+use crate::parser::dialect::AreaDialect;
+
+pub struct Merc;
+
+impl AreaDialect for Merc {
+    fn name(&self) -> &'static str {
+        "Merc"
+    }
+
+    fn sector(&self, number: &str) -> Option<Sector> {
+        merc_sector(number)
+    }
+}
+
+/// Merc only has eleven sectors, with number 8 left unused
+pub fn merc_sector(number: &str) -> Option<Sector> {
+    match number {
+        "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "9" | "10" => Sector::from_str(number),
+        _ => None,
+    }
+}
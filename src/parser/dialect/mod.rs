@@ -0,0 +1,133 @@
+mod avatar;
+mod circle;
+mod merc;
+mod rom;
+mod smaug;
+
+use crate::model::{
+    AreaHeader, ColorCodes, Direction, Door, Mobile, Object, Room, Sector, SectorTable,
+};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::parse_resets::Reset;
+use crate::parser::{parse_header, parse_mobiles, parse_objects, parse_resets, parse_rooms};
+pub use avatar::Avatar;
+pub use circle::Circle;
+pub use merc::Merc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+pub use rom::Rom;
+pub use smaug::Smaug;
+use std::error::Error;
+
+/// The ways DIKU descendants disagree about what goes in an area file. The provided methods read
+/// the Merc layout most dialects share, so each dialect only overrides what it does differently.
+pub trait AreaDialect {
+    fn name(&self) -> &'static str;
+
+    /// Maps a sector number, as written in this dialect's files, onto a [Sector]
+    fn sector(&self, number: &str) -> Option<Sector> {
+        Sector::from_str(number)
+    }
+
+    /// Maps the number after an exit's `D` onto a [Direction]
+    fn direction(&self, number: &str) -> Option<Direction> {
+        Direction::from_str(number)
+    }
+
+    /// Reads the lock field of an exit. Merc and its descendants number the kinds of door there,
+    /// rather than storing flags.
+    fn door(&self, locks: u32) -> Door {
+        Door::from_locks(locks)
+    }
+
+    /// The color codes written in the dialect's strings
+    fn color_codes(&self) -> ColorCodes {
+        ColorCodes::Rom
+    }
+
+    fn parse_header(&self, text: &str) -> Option<AreaHeader> {
+        parse_header::parse_header(text)
+    }
+
+    fn parse_rooms(
+        &self,
+        text: &str,
+        sectors: &SectorTable,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Vec<Room>, Box<dyn Error>> {
+        parse_rooms::parse_rooms(text, self, sectors, diagnostics)
+    }
+
+    fn parse_mobiles(&self, text: &str, diagnostics: &mut Diagnostics) -> Vec<Mobile> {
+        parse_mobiles::parse_mobiles(text, diagnostics)
+    }
+
+    fn parse_objects(&self, text: &str, diagnostics: &mut Diagnostics) -> Vec<Object> {
+        parse_objects::parse_objects(text, diagnostics)
+    }
+
+    fn parse_resets<'a>(
+        &self,
+        text: &'a str,
+        diagnostics: &mut Diagnostics,
+    ) -> Vec<(&'a str, Reset)> {
+        parse_resets::parse_resets(text, diagnostics)
+    }
+}
+
+pub const DIALECT_NAMES: &[&str] = &["avatar", "circle", "merc", "rom", "smaug"];
+
+pub fn dialect_by_name(name: &str) -> Option<Box<dyn AreaDialect>> {
+    let dialect: Box<dyn AreaDialect> = match name.to_ascii_lowercase().as_str() {
+        "avatar" => Box::new(Avatar),
+        "circle" | "tba" => Box::new(Circle),
+        "merc" => Box::new(Merc),
+        "rom" => Box::new(Rom),
+        "smaug" => Box::new(Smaug),
+        _ => return None,
+    };
+    Some(dialect)
+}
+
+static SMAUG_SECTIONS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^#(AUTHOR|RANGES|VERSION|ECONOMY|CLIMATE|FLAGS)\b").unwrap());
+
+static ROM_SECTIONS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^#AREADATA\b|^#AREA\s*$").unwrap());
+
+static MERC_SECTIONS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^#AREA[ \t]+\{").unwrap());
+
+static CIRCLE_WORLD_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\A\s*#\d+\s*$").unwrap());
+
+/// Guesses the dialect from the file's section headers. World files have no sections at all and
+/// start straight away with a room. Files that don't look like anything else are assumed to be
+/// AVATAR's, since that's what areview was written for.
+pub fn detect_dialect(text: &str) -> Box<dyn AreaDialect> {
+    if CIRCLE_WORLD_REGEX.is_match(text.lines().next().unwrap_or_default()) {
+        Box::new(Circle)
+    } else if SMAUG_SECTIONS_REGEX.is_match(text) {
+        Box::new(Smaug)
+    } else if ROM_SECTIONS_REGEX.is_match(text) {
+        Box::new(Rom)
+    } else if MERC_SECTIONS_REGEX.is_match(text) {
+        Box::new(Merc)
+    } else {
+        Box::new(Avatar)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::detect_dialect;
+
+    #[test]
+    fn detects_dialects_from_headers() {
+        let detect = |text| detect_dialect(text).name();
+        assert_eq!(detect("#AREA Sewers~\n#AUTHOR Someone~\n"), "SMAUG");
+        assert_eq!(detect("#AREADATA\nName Sewers~\nEnd\n"), "ROM");
+        assert_eq!(detect("#AREA\nsewers.are~\nSewers~\n"), "ROM");
+        assert_eq!(detect("#AREA\t{ 5 35} Merc    Sewers~\n"), "Merc");
+        assert_eq!(detect("#ROOMS\n#0\n"), "AVATAR");
+        assert_eq!(detect("#18600\nThe Entrance~\n"), "CircleMUD");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::model::{Mobile, Object, Sector, Vnum};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::dialect::merc::merc_sector;
+use crate::parser::dialect::AreaDialect;
+use crate::parser::parse_mobiles::{parse_mobile_section, read_dice};
+use crate::parser::parse_objects::{parse_object_section, read_object_with};
+use crate::parser::reader::{AreaReader, ParseError};
+
+/// ROM adds a race to mobiles and a material and level to objects, writes item types as words, and
+/// puts mana dice, a damage type and four armor classes on mobiles
+pub struct Rom;
+
+impl AreaDialect for Rom {
+    fn name(&self) -> &'static str {
+        "ROM"
+    }
+
+    fn sector(&self, number: &str) -> Option<Sector> {
+        merc_sector(number)
+    }
+
+    fn parse_mobiles(&self, text: &str, diagnostics: &mut Diagnostics) -> Vec<Mobile> {
+        parse_mobile_section(text, read_rom_mobile, diagnostics)
+    }
+
+    fn parse_objects(&self, text: &str, diagnostics: &mut Diagnostics) -> Vec<Object> {
+        parse_object_section(text, read_rom_object, diagnostics)
+    }
+}
+
+/// ROM puts a race after the strings, and mana dice, a damage type and four armor classes in among
+/// the stats; the first armor class, against piercing, stands in for Merc's single one
+fn read_rom_mobile<'a>(reader: &mut AreaReader<'a>, vnum: Vnum) -> Result<Mobile, ParseError<'a>> {
+    let keywords = reader.read_string()?;
+    let short_description = reader.read_string()?;
+    let long_description = reader.read_string()?;
+    let _description = reader.read_string()?;
+    let _race = reader.read_string()?;
+    let act_flags = reader.read_flags("act flags")?;
+    let affect_flags = reader.read_flags("affect flags")?;
+    let alignment = reader.read_number("an alignment")?;
+    let _group = reader.read_number::<i64>("a group")?;
+    let level = reader.read_number("a level")?;
+    let hitroll = reader.read_number("a hitroll")?;
+    let hit_dice = read_dice(reader)?;
+    let _mana_dice = read_dice(reader)?;
+    let damage_dice = read_dice(reader)?;
+    let _damage_type = reader.read_word()?;
+    let armor = reader.read_number("an armor class")?;
+
+    Ok(Mobile {
+        vnum,
+        keywords: keywords.to_string(),
+        short_description: short_description.to_string(),
+        long_description: long_description.to_string(),
+        act_flags,
+        affect_flags,
+        alignment,
+        level,
+        hitroll,
+        armor,
+        hit_dice,
+        damage_dice,
+    })
+}
+
+/// ROM objects have a material in place of the action description, and a level before the weight
+fn read_rom_object<'a>(reader: &mut AreaReader<'a>, vnum: Vnum) -> Result<Object, ParseError<'a>> {
+    let keywords = reader.read_string()?;
+    let short_description = reader.read_string()?;
+    let long_description = reader.read_string()?;
+    let _material = reader.read_string()?;
+    read_object_with(
+        reader,
+        vnum,
+        [keywords, short_description, long_description],
+        true,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rom;
+    use crate::model::{Dice, ItemType};
+    use crate::parser::diagnostic::Diagnostics;
+    use crate::parser::dialect::AreaDialect;
+
+    const AREA: &str = "#MOBILES
+#3000
+wizard~
+the wizard~
+A wizard walks around behind the counter, talking to himself.
+~
+The wizard looks old and senile.
+~
+human~
+ABV DF 900 0
+33 20 10d10+500 100d10+100 5d4+10 pierce
+-10 -10 -10 -5
+EFK 0 0 0
+stand stand male 200
+AHMV ABCDEFHJKUV medium unknown
+#0
+
+#OBJECTS
+#3014
+portal shimmer~
+a shimmering portal~
+A shimmering portal hangs in the air.~
+energy~
+portal 0 0
+0 0 0 3001 0
+0 0 0 P
+#3015
+scroll parchment~
+a parchment scroll~
+A parchment scroll lies here.~
+paper~
+scroll AG A
+20 'cure light' 'armor' '' ''
+5 1 200 P
+A
+12 5
+#0
+";
+
+    #[test]
+    fn parses_rom_mobiles() {
+        let mobiles = Rom.parse_mobiles(AREA, &mut Diagnostics::new("test.are", AREA));
+        assert_eq!(mobiles.len(), 1);
+        let wizard = &mobiles[0];
+        assert_eq!(wizard.short_description, "the wizard");
+        assert_eq!(wizard.act_flags, 0b1 | 0b10 | 1 << 21);
+        assert_eq!(wizard.level, 33);
+        assert_eq!(wizard.hitroll, 20);
+        assert_eq!(
+            wizard.damage_dice,
+            Dice {
+                number: 5,
+                size: 4,
+                bonus: 10
+            }
+        );
+        assert_eq!(wizard.armor, -10);
+    }
+
+    #[test]
+    fn parses_rom_objects() {
+        let objects = Rom.parse_objects(AREA, &mut Diagnostics::new("test.are", AREA));
+        assert_eq!(objects.len(), 2);
+
+        assert_eq!(objects[0].item_type, ItemType::Portal);
+        assert_eq!(objects[0].portal_destination(), Some(3001));
+
+        let scroll = &objects[1];
+        assert_eq!(scroll.item_type, ItemType::Scroll);
+        assert_eq!(scroll.values[1], "cure light");
+        assert_eq!(scroll.values[3], "");
+        assert_eq!(scroll.weight, 1);
+        assert_eq!(scroll.cost, 200);
+        assert_eq!(scroll.affects.len(), 1);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::model::{AreaHeader, ColorCodes, Door, Sector};
+use crate::parser::dialect::AreaDialect;
+use crate::parser::parse_header::parse_header;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// SMAUG keeps the Merc layout for rooms, mobiles and objects, but moves the author and level
+/// range out of the `#AREA` line into sections of their own, and renumbers the sectors
+pub struct Smaug;
+
+static AUTHOR_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^#AUTHOR[ \t]+(?P<author>[^~\n]*)~").unwrap());
+
+static RANGES_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^#RANGES\s*$\s*(?P<low>\d+)\s+(?P<high>\d+)").unwrap());
+
+impl AreaDialect for Smaug {
+    fn name(&self) -> &'static str {
+        "SMAUG"
+    }
+
+    fn color_codes(&self) -> ColorCodes {
+        ColorCodes::Smaug
+    }
+
+    fn sector(&self, number: &str) -> Option<Sector> {
+        use Sector::*;
+        match number {
+            "8" => Some(Underwater),
+            "9" => Some(Air),
+            "10" => Some(Desert),
+            "12" => Some(OnBottom),
+            "13" => Some(Underground),
+            "14" => Some(Lava),
+            "15" => Some(Swamp),
+            "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" => Sector::from_str(number),
+            _ => None,
+        }
+    }
+
+    /// SMAUG writes the exit flags themselves instead of a kind of door
+    fn door(&self, locks: u32) -> Door {
+        Door::from_bits(locks)
+    }
+
+    fn parse_header(&self, text: &str) -> Option<AreaHeader> {
+        let mut header = parse_header(text)?;
+        if let Some(captures) = AUTHOR_REGEX.captures(text) {
+            header.builders = Some(captures["author"].trim().to_string());
+        }
+        if let Some(captures) = RANGES_REGEX.captures(text) {
+            header.levels = Some((
+                captures["low"].parse().ok()?,
+                captures["high"].parse().ok()?,
+            ));
+        }
+        Some(header)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Smaug;
+    use crate::model::{AreaHeader, Door, Sector};
+    use crate::parser::dialect::AreaDialect;
+
+    #[test]
+    fn reads_author_and_ranges() {
+        let text = "#AREA   The Sewers~\n\n#AUTHOR Someone~\n\n#RANGES\n5 20 0 50\n$\n";
+        assert_eq!(
+            Smaug.parse_header(text),
+            Some(AreaHeader {
+                name: "The Sewers".to_string(),
+                builders: Some("Someone".to_string()),
+                levels: Some((5, 20)),
+                vnums: None,
+                security: None,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn renumbers_sectors() {
+        assert_eq!(Smaug.sector("8"), Some(Sector::Underwater));
+        assert_eq!(Smaug.sector("15"), Some(Sector::Swamp));
+        assert_eq!(Smaug.sector("3"), Some(Sector::Forest));
+    }
+
+    #[test]
+    fn reads_exit_flags() {
+        let door = Smaug.door(1 | 2 | 4 | 32);
+        assert!(door.is_door && door.closed && door.locked && door.pickproof);
+        assert!(!door.secret);
+        assert_eq!(Smaug.door(0), Door::NONE);
+    }
+}
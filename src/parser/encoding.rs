@@ -0,0 +1,89 @@
+/// The character sets area files turn up in. Old areas were written long before UTF-8, usually
+/// in whatever the builder's terminal or editor used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Latin1,
+    Cp437,
+}
+
+pub const ENCODING_NAMES: &[&str] = &["utf8", "latin1", "cp437"];
+
+pub fn encoding_by_name(name: &str) -> Option<Encoding> {
+    match name.to_ascii_lowercase().replace('-', "").as_str() {
+        "utf8" => Some(Encoding::Utf8),
+        "latin1" | "iso88591" => Some(Encoding::Latin1),
+        "cp437" | "ibm437" | "dos" => Some(Encoding::Cp437),
+        _ => None,
+    }
+}
+
+impl Encoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf8",
+            Encoding::Latin1 => "latin1",
+            Encoding::Cp437 => "cp437",
+        }
+    }
+
+    /// Guesses the encoding of a file. Anything that's valid UTF-8 is taken to be UTF-8. Otherwise
+    /// bytes 0x80-0x9F give CP437 away, as that's where it keeps its accented letters, while
+    /// Latin-1 only has control characters there.
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        if std::str::from_utf8(bytes).is_ok() {
+            Encoding::Utf8
+        } else if bytes.iter().any(|b| (0x80..=0x9f).contains(b)) {
+            Encoding::Cp437
+        } else {
+            Encoding::Latin1
+        }
+    }
+
+    pub fn decode(&self, bytes: Vec<u8>) -> Result<String, std::string::FromUtf8Error> {
+        match self {
+            Encoding::Utf8 => String::from_utf8(bytes),
+            // Latin-1 is the first 256 code points of Unicode
+            Encoding::Latin1 => Ok(bytes.into_iter().map(char::from).collect()),
+            Encoding::Cp437 => Ok(bytes
+                .into_iter()
+                .map(|b| match b {
+                    0x00..=0x7f => char::from(b),
+                    _ => CP437_HIGH[b as usize - 0x80],
+                })
+                .collect()),
+        }
+    }
+}
+
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}', //
+];
+
+#[cfg(test)]
+mod test {
+    use super::Encoding;
+
+    #[test]
+    fn detects_and_decodes_legacy_encodings() {
+        let utf8 = "Café Élise~".as_bytes().to_vec();
+        assert_eq!(Encoding::detect(&utf8), Encoding::Utf8);
+
+        let latin1 = b"Caf\xe9 \xc9lise~".to_vec();
+        assert_eq!(Encoding::detect(&latin1), Encoding::Latin1);
+        assert_eq!(Encoding::Latin1.decode(latin1).unwrap(), "Café Élise~");
+
+        let cp437 = b"Caf\x82 \x90lise \xc9\xcd\xbb~".to_vec();
+        assert_eq!(Encoding::detect(&cp437), Encoding::Cp437);
+        assert_eq!(Encoding::Cp437.decode(cp437).unwrap(), "Café Élise ╔═╗~");
+
+        assert!(Encoding::Utf8.decode(b"Caf\xe9".to_vec()).is_err());
+    }
+}
@@ -1,35 +1,257 @@
+mod area_list;
 mod connection;
+mod diagnostic;
+mod dialect;
+mod encoding;
+mod parse_header;
+mod parse_mobiles;
+mod parse_objects;
+mod parse_resets;
 mod parse_rooms;
+mod parse_wld;
+mod reader;
 mod rule;
+mod section;
 mod sort_rooms;
+mod stack_planes;
 
-use crate::model::{Room, Vnum};
+use crate::model::{AreaHeader, ColorCodes, Mobile, Object, Room, RoomSpawns, SectorTable, Vnum};
+#[cfg(test)]
+pub use connection::find_connections;
 pub use connection::{Connection, Exit};
+pub use diagnostic::{Diagnostic, Diagnostics, Severity};
+pub use dialect::{detect_dialect, dialect_by_name, AreaDialect, DIALECT_NAMES};
+pub use encoding::{encoding_by_name, Encoding, ENCODING_NAMES};
 use fnv::FnvHashMap;
 pub use rule::{ParseRuleError, Rule};
-pub use sort_rooms::Location;
+#[cfg(test)]
+pub use sort_rooms::rooms_by_vnum;
+pub use sort_rooms::{Collision, CollisionFix, Layout, Location, RoomsByVnum};
+pub use stack_planes::{stack_planes, Floor, Stacking, StairMismatch, StairProblem};
 use std::error::Error;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+/// One of the files that make up the world
+pub struct LoadedArea {
+    pub path: PathBuf,
+    pub dialect: &'static str,
+    pub encoding: Encoding,
+    pub header: AreaHeader,
+}
+
 pub struct ParsedArea {
-    pub all_rooms: FnvHashMap<Vnum, (Rc<Room>, usize)>,
+    pub areas: Vec<LoadedArea>,
+    /// Which of `areas` each room was loaded from
+    pub room_areas: FnvHashMap<Vnum, usize>,
+    pub all_rooms: RoomsByVnum,
+    pub mobiles: Vec<Mobile>,
+    pub objects: Vec<Object>,
+    pub spawns: FnvHashMap<Vnum, RoomSpawns>,
     pub grouped_rooms: Vec<Vec<Location>>,
     pub connections: Vec<Connection>,
+    /// Rooms the layout left on top of other rooms
+    pub collisions: Vec<Collision>,
+    /// Which floor each plane is on
+    pub stacking: Stacking,
+}
+
+impl ParsedArea {
+    /// The color codes of the area room `vnum` was loaded from
+    pub fn color_codes(&self, vnum: Vnum) -> ColorCodes {
+        match self.room_areas.get(&vnum) {
+            Some(&idx) => self.areas[idx].header.color_codes,
+            None => ColorCodes::default(),
+        }
+    }
+
+    /// The vnums the area at `idx` claims, or if it doesn't say, the span of the rooms it has
+    pub fn vnum_range(&self, idx: usize) -> Option<(Vnum, Vnum)> {
+        self.areas[idx].header.vnums.or_else(|| {
+            let vnums = self
+                .room_areas
+                .iter()
+                .filter(|(_, &room_area)| room_area == idx)
+                .map(|(&vnum, _)| vnum);
+            Some((vnums.clone().min()?, vnums.max()?))
+        })
+    }
 }
 
-pub fn load_area(path: &dyn AsRef<Path>, rules: Vec<Rule>) -> Result<ParsedArea, Box<dyn Error>> {
-    let file = std::fs::read_to_string(path)?;
-    let rooms = parse_rooms::parse_rooms(&file)?;
+/// Loads area files, and the files listed in any `.lst` among them, into one world. Each is read as
+/// `dialect` and decoded from `encoding`, or as whatever each looks like if they're `None`.
+/// Problems that don't stop the world from loading are returned alongside it.
+pub fn load_area(
+    paths: &[PathBuf],
+    layout: &Layout,
+    dialect: Option<&dyn AreaDialect>,
+    encoding: Option<Encoding>,
+    sectors: &SectorTable,
+) -> Result<(ParsedArea, Vec<Diagnostic>), Box<dyn Error>> {
+    let paths = area_list::expand_area_lists(paths)?;
+    let (encodings, texts): (Vec<_>, Vec<_>) = paths
+        .iter()
+        .map(|path| read_area_file(path, encoding))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+
+    let mut areas = vec![];
+    let mut files = vec![];
+    let mut rooms: Vec<Room> = vec![];
+    let mut room_areas: FnvHashMap<Vnum, usize> = FnvHashMap::default();
+    let mut mobiles: Vec<Mobile> = vec![];
+    let mut objects: Vec<Object> = vec![];
+    let mut mobile_areas: FnvHashMap<Vnum, usize> = FnvHashMap::default();
+    let mut object_areas: FnvHashMap<Vnum, usize> = FnvHashMap::default();
+    for (area_idx, ((path, text), &encoding)) in
+        paths.iter().zip(&texts).zip(&encodings).enumerate()
+    {
+        let mut diagnostics = Diagnostics::new(path.display().to_string(), text);
+        let detected;
+        let dialect = match dialect {
+            Some(dialect) => dialect,
+            None => {
+                detected = detect_dialect(text);
+                detected.as_ref()
+            }
+        };
+        let mut header = dialect.parse_header(text).unwrap_or_else(|| AreaHeader {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            ..Default::default()
+        });
+        header.color_codes = dialect.color_codes();
+
+        // Area lists usually name a few files without rooms, such as the helps
+        let area_rooms = match dialect.parse_rooms(text, sectors, &mut diagnostics) {
+            Ok(area_rooms) => area_rooms,
+            Err(e) if paths.len() > 1 => {
+                diagnostics.warning_at(None, e);
+                vec![]
+            }
+            Err(e) => return Err(e),
+        };
+        for room in area_rooms {
+            let at = section::find_entry(text, "ROOMS", room.vnum);
+            if !header.contains_vnum(room.vnum) {
+                let (low, high) = header.vnums.unwrap();
+                diagnostics.warning_at(
+                    at,
+                    format!(
+                        "Room {} is outside the area's vnum range {}-{}",
+                        room.vnum, low, high
+                    ),
+                );
+            }
+            if let Some(&other) = room_areas.get(&room.vnum) {
+                let other: &LoadedArea = &areas[other];
+                diagnostics.warning_at(
+                    at,
+                    format!(
+                        "Room {} was already loaded from {}",
+                        room.vnum,
+                        other.path.display()
+                    ),
+                );
+                continue;
+            }
+            room_areas.insert(room.vnum, area_idx);
+            rooms.push(room);
+        }
+
+        for mobile in dialect.parse_mobiles(text, &mut diagnostics) {
+            if let Some(&other) = mobile_areas.get(&mobile.vnum) {
+                diagnostics.warning_at(
+                    section::find_entry(text, "MOBILES", mobile.vnum),
+                    format!(
+                        "Mobile {} was already loaded from {}",
+                        mobile.vnum,
+                        areas[other].path.display()
+                    ),
+                );
+                continue;
+            }
+            mobile_areas.insert(mobile.vnum, area_idx);
+            mobiles.push(mobile);
+        }
+        for object in dialect.parse_objects(text, &mut diagnostics) {
+            if let Some(&other) = object_areas.get(&object.vnum) {
+                diagnostics.warning_at(
+                    section::find_entry(text, "OBJECTS", object.vnum),
+                    format!(
+                        "Object {} was already loaded from {}",
+                        object.vnum,
+                        areas[other].path.display()
+                    ),
+                );
+                continue;
+            }
+            object_areas.insert(object.vnum, area_idx);
+            objects.push(object);
+        }
+        let resets = dialect.parse_resets(text, &mut diagnostics);
+        files.push((diagnostics, resets));
+
+        areas.push(LoadedArea {
+            path: path.clone(),
+            dialect: dialect.name(),
+            encoding,
+            header,
+        });
+    }
+    if rooms.is_empty() {
+        return Err("None of the area files have any rooms".into());
+    }
+
+    // Resets can load mobiles and objects from other areas, so they wait until everything's loaded
+    let mut spawns: FnvHashMap<Vnum, RoomSpawns> = FnvHashMap::default();
+    let mut all_diagnostics = vec![];
+    for (mut diagnostics, resets) in files {
+        let file_spawns =
+            parse_resets::apply_resets(&resets, &mut rooms, &mobiles, &objects, &mut diagnostics);
+        for (vnum, room_spawns) in file_spawns {
+            spawns.entry(vnum).or_default().merge(room_spawns);
+        }
+        all_diagnostics.extend(diagnostics.into_vec());
+    }
 
     let rooms: Vec<_> = rooms.into_iter().map(Rc::new).collect();
     let connections = connection::find_connections(&rooms);
 
-    let (all_rooms, grouped_rooms) = sort_rooms::sort_rooms(rooms, rules);
+    let (all_rooms, grouped_rooms, collisions) = sort_rooms::sort_rooms(rooms, layout);
+    let stacking = stack_planes(&grouped_rooms);
 
-    Ok(ParsedArea {
+    let area = ParsedArea {
+        areas,
+        room_areas,
         all_rooms,
+        mobiles,
+        objects,
+        spawns,
         grouped_rooms,
         connections,
-    })
+        collisions,
+        stacking,
+    };
+    Ok((area, all_diagnostics))
+}
+
+fn read_area_file(
+    path: &Path,
+    encoding: Option<Encoding>,
+) -> Result<(Encoding, String), Box<dyn Error>> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let encoding = encoding.unwrap_or_else(|| Encoding::detect(&bytes));
+    let text = encoding.decode(bytes).map_err(|e| {
+        format!(
+            "{}: {}, try naming its encoding with one of {}",
+            path.display(),
+            e,
+            ENCODING_NAMES.join(", ")
+        )
+    })?;
+    Ok((encoding, text))
 }
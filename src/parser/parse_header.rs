@@ -0,0 +1,181 @@
+use crate::model::AreaHeader;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+/// Reads the area header from either an `#AREADATA` block, a ROM-style multi-line `#AREA`, or a
+/// Merc-style one-line `#AREA`, in that order of preference
+pub fn parse_header(text: &str) -> Option<AreaHeader> {
+    if let Some(captures) = AREADATA_REGEX.captures(text) {
+        return Some(parse_areadata(&captures["body"]));
+    }
+
+    if let Some(captures) = ROM_AREA_REGEX.captures(text) {
+        let (levels, builders, _) = parse_credits(&captures["credits"]);
+        return Some(AreaHeader {
+            name: captures["name"].trim().to_string(),
+            builders,
+            levels,
+            vnums: parse_range(&captures["low"], &captures["high"]),
+            security: None,
+            ..Default::default()
+        });
+    }
+
+    if let Some(captures) = MERC_AREA_REGEX.captures(text) {
+        let (levels, builders, name) = parse_credits(&captures["credits"]);
+        return Some(AreaHeader {
+            name,
+            builders,
+            levels,
+            vnums: None,
+            security: None,
+            ..Default::default()
+        });
+    }
+
+    None
+}
+
+static AREADATA_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?ims)^#AREADATA\s*$(?P<body>.*?)^End\s*$").unwrap());
+
+static ROM_AREA_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?mx)
+        ^\#AREA\s*$\s*
+        [^~]*~\s*
+        (?P<name>[^~]*)~\s*
+        (?P<credits>[^~]*)~\s*
+        (?P<low>\d+)\s+(?P<high>\d+)",
+    )
+    .unwrap()
+});
+
+static MERC_AREA_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^#AREA[ \t]+(?P<credits>[^~\n]*)~").unwrap());
+
+static CREDITS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\A\s*\{(?P<levels>[^}]*)\}\s*(?P<builder>\S*)\s*(?P<name>.*?)\s*\z").unwrap()
+});
+
+fn parse_areadata(body: &str) -> AreaHeader {
+    let mut header = AreaHeader::default();
+    for line in body.lines() {
+        let (key, value) = match line.trim().split_once(char::is_whitespace) {
+            Some((key, value)) => (key, value.trim().trim_end_matches('~').trim()),
+            None => continue,
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "name" => header.name = value.to_string(),
+            "builders" => header.builders = Some(value.to_string()),
+            "vnums" => {
+                if let Some((low, high)) = value.split_once(char::is_whitespace) {
+                    header.vnums = parse_range(low, high);
+                }
+            }
+            "credits" => header.levels = parse_credits(value).0,
+            "security" => header.security = value.parse().ok(),
+            _ => {}
+        }
+    }
+    header
+}
+
+type Credits = (Option<(u32, u32)>, Option<String>, String);
+
+/// Splits a credits string like `{ 5 35} Merc    Mud School` into the level range, the builder
+/// and the area name
+fn parse_credits(credits: &str) -> Credits {
+    let captures = match CREDITS_REGEX.captures(credits) {
+        Some(captures) => captures,
+        None => return (None, None, credits.trim().to_string()),
+    };
+
+    let levels: Vec<_> = captures["levels"].split_whitespace().collect();
+    let levels = match levels[..] {
+        [low, high] => parse_range(low, high),
+        _ => None,
+    };
+    let builder = non_empty(&captures, "builder");
+    let name = non_empty(&captures, "name").unwrap_or_default();
+    (levels, builder, name)
+}
+
+fn non_empty(captures: &Captures, name: &str) -> Option<String> {
+    Some(captures[name].to_string()).filter(|s| !s.is_empty())
+}
+
+fn parse_range(low: &str, high: &str) -> Option<(u32, u32)> {
+    Some((low.trim().parse().ok()?, high.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_header;
+    use crate::model::AreaHeader;
+
+    #[test]
+    fn parses_merc_header() {
+        assert_eq!(
+            parse_header("#AREA\t{ 5 35} Merc    Mud School~\n\n#ROOMS\n#0\n"),
+            Some(AreaHeader {
+                name: "Mud School".to_string(),
+                builders: Some("Merc".to_string()),
+                levels: Some((5, 35)),
+                vnums: None,
+                security: None,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_rom_header() {
+        let text = "#AREA\nmidgaard.are~\nMidgaard~\n{ ALL } Diku    Midgaard~\n3000 3399\n";
+        assert_eq!(
+            parse_header(text),
+            Some(AreaHeader {
+                name: "Midgaard".to_string(),
+                builders: Some("Diku".to_string()),
+                levels: None,
+                vnums: Some((3000, 3399)),
+                security: None,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_areadata_header() {
+        let text = "#AREADATA
+Name Haon Dor~
+Builders Alice Bob~
+VNUMs 6000 6099
+Credits {10 20} Alice  Haon Dor~
+Security 9
+End
+
+#ROOMS
+#0
+";
+        let header = parse_header(text).unwrap();
+        assert_eq!(
+            header,
+            AreaHeader {
+                name: "Haon Dor".to_string(),
+                builders: Some("Alice Bob".to_string()),
+                levels: Some((10, 20)),
+                vnums: Some((6000, 6099)),
+                security: Some(9),
+                ..Default::default()
+            }
+        );
+        assert!(header.contains_vnum(6000));
+        assert!(!header.contains_vnum(6100));
+    }
+
+    #[test]
+    fn missing_header() {
+        assert_eq!(parse_header("#ROOMS\n#0\n"), None);
+    }
+}
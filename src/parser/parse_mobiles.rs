@@ -0,0 +1,164 @@
+use crate::model::{Dice, Mobile, Vnum};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::reader::{AreaReader, ParseError};
+use crate::parser::section::{find_section, read_entries};
+use fnv::FnvHashSet;
+
+pub type MobileParser = for<'a> fn(&mut AreaReader<'a>, Vnum) -> Result<Mobile, ParseError<'a>>;
+
+/// Parses a Merc-style `#MOBILES` section
+pub fn parse_mobiles(text: &str, diagnostics: &mut Diagnostics) -> Vec<Mobile> {
+    parse_mobile_section(text, read_mobile, diagnostics)
+}
+
+/// Parses the `#MOBILES` section, using `read_mobile` for each entry. Areas without one are valid
+/// and just have no mobiles.
+pub fn parse_mobile_section(
+    text: &str,
+    read_mobile: MobileParser,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Mobile> {
+    let mut reader = match find_section(text, "MOBILES") {
+        Some(reader) => reader,
+        None => return vec![],
+    };
+
+    let entries = read_entries(&mut reader, diagnostics, |reader, vnum, _| {
+        let mobile = read_mobile(reader, vnum)?;
+        reader.skip_entry()?;
+        Ok(mobile)
+    });
+
+    let mut mobiles: Vec<Mobile> = vec![];
+    let mut seen = FnvHashSet::default();
+    for (vnum_text, mobile) in entries {
+        if !seen.insert(mobile.vnum) {
+            diagnostics.warning(vnum_text, format!("Duplicate mobile VNUM {}", mobile.vnum));
+        } else {
+            mobiles.push(mobile);
+        }
+    }
+
+    mobiles
+}
+
+/// Reads the fields areview uses, leaving the rest of the entry to be skipped. SMAUG writes `C`
+/// instead of `S` for mobiles with more stat lines, which all come after these.
+fn read_mobile<'a>(reader: &mut AreaReader<'a>, vnum: Vnum) -> Result<Mobile, ParseError<'a>> {
+    let keywords = reader.read_string()?;
+    let short_description = reader.read_string()?;
+    let long_description = reader.read_string()?;
+    let _description = reader.read_string()?;
+    let act_flags = reader.read_flags("act flags")?;
+    let affect_flags = reader.read_flags("affect flags")?;
+    let alignment = reader.read_number("an alignment")?;
+    let kind = reader.read_word()?;
+    if kind != "S" && kind != "C" {
+        return Err(ParseError::new(
+            kind,
+            format!("Expected `S` or `C`, found `{kind}`"),
+        ));
+    }
+
+    Ok(Mobile {
+        vnum,
+        keywords: keywords.to_string(),
+        short_description: short_description.to_string(),
+        long_description: long_description.to_string(),
+        act_flags,
+        affect_flags,
+        alignment,
+        level: reader.read_number("a level")?,
+        hitroll: reader.read_number("a hitroll")?,
+        armor: reader.read_number("an armor class")?,
+        hit_dice: read_dice(reader)?,
+        damage_dice: read_dice(reader)?,
+    })
+}
+
+/// Reads a dice expression such as `3d8+120`
+pub fn read_dice<'a>(reader: &mut AreaReader<'a>) -> Result<Dice, ParseError<'a>> {
+    let word = reader.read_word()?;
+    Dice::from_str(word).ok_or_else(|| ParseError::new(word, format!("Invalid dice `{word}`")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_mobiles;
+    use crate::model::Dice;
+    use crate::parser::diagnostic::Diagnostics;
+
+    const AREA: &str = "#MOBILES
+#3000
+wizard~
+the wizard~
+A wizard walks around behind the counter, talking to himself.
+~
+The wizard looks old and senile.
+~
+1|2|64 0 900 S
+33 0 -5 10d10+500 2d8-1
+0 0
+8 8 1
+#3001
+baker~
+the baker~
+The baker looks at you calmly.
+~
+~
+AB 32 -350 S
+12 2 3 1d12+100 1d6+2
+0 0
+8 8 2
+#0
+
+#ROOMS
+#0
+";
+
+    #[test]
+    fn parses_mobiles() {
+        let mobiles = parse_mobiles(AREA, &mut Diagnostics::new("test.are", AREA));
+        assert_eq!(mobiles.len(), 2);
+
+        let wizard = &mobiles[0];
+        assert_eq!(wizard.vnum, 3000);
+        assert_eq!(wizard.keywords, "wizard");
+        assert_eq!(wizard.short_description, "the wizard");
+        assert_eq!(
+            wizard.long_description,
+            "A wizard walks around behind the counter, talking to himself.\n"
+        );
+        assert_eq!(wizard.act_flags, 67);
+        assert_eq!(wizard.affect_flags, 0);
+        assert_eq!(wizard.alignment, 900);
+        assert_eq!(wizard.level, 33);
+        assert_eq!(wizard.armor, -5);
+        assert_eq!(
+            wizard.hit_dice,
+            Dice {
+                number: 10,
+                size: 10,
+                bonus: 500
+            }
+        );
+        assert_eq!(
+            wizard.damage_dice,
+            Dice {
+                number: 2,
+                size: 8,
+                bonus: -1
+            }
+        );
+
+        let baker = &mobiles[1];
+        assert_eq!(baker.act_flags, 3);
+        assert_eq!(baker.affect_flags, 32);
+        assert_eq!(baker.alignment, -350);
+    }
+
+    #[test]
+    fn missing_section_has_no_mobiles() {
+        assert!(parse_mobiles("#ROOMS\n#0\n", &mut Diagnostics::new("test.are", "")).is_empty());
+    }
+}
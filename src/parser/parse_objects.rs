@@ -0,0 +1,267 @@
+use crate::model::{ItemType, Object, ObjectAffect, Vnum};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::parse_rooms::read_extra_description;
+use crate::parser::reader::{AreaReader, ParseError};
+use crate::parser::section::{find_section, read_entries};
+use fnv::FnvHashSet;
+
+pub type ObjectParser = for<'a> fn(&mut AreaReader<'a>, Vnum) -> Result<Object, ParseError<'a>>;
+
+/// Parses a Merc-style `#OBJECTS` section
+pub fn parse_objects(text: &str, diagnostics: &mut Diagnostics) -> Vec<Object> {
+    parse_object_section(text, read_object, diagnostics)
+}
+
+/// Parses the `#OBJECTS` section, using `read_object` for each entry up to its extra descriptions
+/// and affects. Areas without one are valid and just have no objects.
+pub fn parse_object_section(
+    text: &str,
+    read_object: ObjectParser,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Object> {
+    let mut reader = match find_section(text, "OBJECTS") {
+        Some(reader) => reader,
+        None => return vec![],
+    };
+
+    let entries = read_entries(&mut reader, diagnostics, |reader, vnum, _| {
+        let mut object = read_object(reader, vnum)?;
+        read_object_extras(reader, &mut object)?;
+        Ok(object)
+    });
+
+    let mut objects: Vec<Object> = vec![];
+    let mut seen = FnvHashSet::default();
+    for (vnum_text, object) in entries {
+        if !seen.insert(object.vnum) {
+            diagnostics.warning(vnum_text, format!("Duplicate object VNUM {}", object.vnum));
+        } else {
+            objects.push(object);
+        }
+    }
+
+    objects
+}
+
+fn read_object<'a>(reader: &mut AreaReader<'a>, vnum: Vnum) -> Result<Object, ParseError<'a>> {
+    let keywords = reader.read_string()?;
+    let short_description = reader.read_string()?;
+    let long_description = reader.read_string()?;
+    let _action_description = reader.read_string()?;
+    read_object_with(
+        reader,
+        vnum,
+        [keywords, short_description, long_description],
+        false,
+    )
+}
+
+/// Reads the lines after an object's strings, which are laid out the same way by most dialects.
+/// ROM puts the level in front of the weight.
+pub fn read_object_with<'a>(
+    reader: &mut AreaReader<'a>,
+    vnum: Vnum,
+    [keywords, short_description, long_description]: [&str; 3],
+    leading_level: bool,
+) -> Result<Object, ParseError<'a>> {
+    let item_type_text = reader.read_word()?;
+    let item_type = ItemType::from_str(item_type_text).ok_or_else(|| {
+        ParseError::new(
+            item_type_text,
+            format!("Invalid item type `{item_type_text}`"),
+        )
+    })?;
+    let extra_flags = reader.read_flags("extra flags")?;
+    let wear_flags = reader.read_flags("wear flags")?;
+    // SMAUG adds layers
+    reader.read_rest_of_line();
+
+    let values_line = reader.read_line()?;
+    let values = parse_values(values_line)
+        .ok_or_else(|| ParseError::new(values_line, format!("Invalid values for object {vnum}")))?;
+
+    if leading_level {
+        reader.read_number::<i32>("a level")?;
+    }
+    let weight = reader.read_number("a weight")?;
+    let cost = reader.read_number("a cost")?;
+    reader.read_rest_of_line();
+
+    Ok(Object {
+        vnum,
+        keywords: keywords.to_string(),
+        short_description: short_description.to_string(),
+        long_description: long_description.to_string(),
+        item_type,
+        extra_flags,
+        wear_flags,
+        values,
+        weight,
+        cost,
+        affects: vec![],
+        extra_descriptions: vec![],
+    })
+}
+
+/// Reads the extra descriptions and affects that close an object entry
+fn read_object_extras<'a>(
+    reader: &mut AreaReader<'a>,
+    object: &mut Object,
+) -> Result<(), ParseError<'a>> {
+    while !matches!(reader.peek(), None | Some('#') | Some('$')) {
+        let word = reader.read_word()?;
+        match word {
+            "E" => object
+                .extra_descriptions
+                .push(read_extra_description(reader)?),
+            "A" => object.affects.push(ObjectAffect {
+                location: reader.read_number("an affect location")?,
+                modifier: reader.read_number("an affect modifier")?,
+            }),
+            // ROM's affects that set flags on the wearer, laid out as `F`, `A 0 0 flags`
+            "F" => {
+                reader.read_word()?;
+                reader.read_number::<i32>("an affect location")?;
+                reader.read_number::<i32>("an affect modifier")?;
+                reader.read_flags("affect flags")?;
+            }
+            _ => reader.skip_line(word)?,
+        }
+    }
+    Ok(())
+}
+
+/// Splits the values line into v0 through v4. Merc writes four values, ROM five and SMAUG six;
+/// anything past v4 isn't kept. ROM quotes spell names, which can contain spaces.
+fn parse_values(line: &str) -> Option<[String; 5]> {
+    let mut written = vec![];
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let (value, remainder) = match rest.strip_prefix('\'') {
+            Some(quoted) => {
+                let end = quoted.find('\'')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())),
+        };
+        written.push(value.to_string());
+        rest = remainder.trim_start();
+    }
+
+    if written.len() < 4 || written.len() > 6 {
+        return None;
+    }
+    let mut values: [String; 5] = Default::default();
+    for (value, written) in values.iter_mut().zip(written) {
+        *value = written;
+    }
+    Some(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_objects;
+    use crate::model::{ItemType, ObjectAffect};
+    use crate::parser::diagnostic::Diagnostics;
+
+    const AREA: &str = "#OBJECTS
+#3010
+bread loaf~
+a loaf of bread~
+A loaf of bread lies here.~
+~
+19 0 1
+24 0 0 0
+1 5 0
+E
+bread loaf~
+It looks tasty.
+~
+#3011
+chest~
+an iron chest~
+An iron chest sits in the corner.~
+~
+15 0 0
+100 5 3012 0
+50 200 0
+#3012
+key iron~
+an iron key~
+A small iron key lies here.~
+~
+18 0 1
+0 0 0 0
+1 10 0
+#3013
+ring~
+a gold ring~
+A gold ring glints in the dirt.~
+~
+9 2 3
+5 0 0 0
+1 1000 0
+A
+18 2
+A
+19 -1
+#3014
+portal shimmer~
+a shimmering portal~
+A shimmering portal hangs in the air.~
+~
+portal 0 0
+0 0 0 3001 0
+0 0 0
+#0
+";
+
+    #[test]
+    fn parses_objects() {
+        let objects = parse_objects(AREA, &mut Diagnostics::new("test.are", AREA));
+        assert_eq!(objects.len(), 5);
+
+        let bread = &objects[0];
+        assert_eq!(bread.vnum, 3010);
+        assert_eq!(bread.short_description, "a loaf of bread");
+        assert_eq!(bread.item_type, ItemType::Food);
+        assert_eq!(bread.wear_flags, 1);
+        assert_eq!(bread.value(0), Some(24));
+        assert_eq!(bread.values[4], "");
+        assert_eq!(bread.weight, 1);
+        assert_eq!(bread.cost, 5);
+        assert_eq!(bread.extra_descriptions.len(), 1);
+        assert_eq!(bread.extra_descriptions[0].keywords, "bread loaf");
+
+        let ring = &objects[3];
+        assert_eq!(ring.item_type, ItemType::Armor);
+        assert_eq!(ring.extra_flags, 2);
+        assert_eq!(
+            ring.affects,
+            vec![
+                ObjectAffect {
+                    location: 18,
+                    modifier: 2
+                },
+                ObjectAffect {
+                    location: 19,
+                    modifier: -1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_keys_and_portals() {
+        let objects = parse_objects(AREA, &mut Diagnostics::new("test.are", AREA));
+
+        let chest = &objects[1];
+        assert_eq!(chest.container_key(), Some(3012));
+        assert_eq!(objects[2].item_type, ItemType::Key);
+        assert_eq!(objects[2].container_key(), None);
+
+        let portal = &objects[4];
+        assert_eq!(portal.item_type, ItemType::Portal);
+        assert_eq!(portal.portal_destination(), Some(3001));
+    }
+}
@@ -0,0 +1,367 @@
+use crate::model::{
+    Direction, DoorState, Mobile, Object, Room, RoomSpawns, SpawnedMobile, SpawnedObject, Vnum,
+};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::section::find_section;
+use fnv::{FnvHashMap, FnvHashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reset {
+    Mobile {
+        vnum: Vnum,
+        room: Vnum,
+    },
+    Object {
+        vnum: Vnum,
+        room: Vnum,
+    },
+    Put {
+        vnum: Vnum,
+        container: Vnum,
+    },
+    Give {
+        vnum: Vnum,
+    },
+    Equip {
+        vnum: Vnum,
+        wear_location: i32,
+    },
+    Door {
+        room: Vnum,
+        direction: Direction,
+        state: DoorState,
+    },
+    Randomize {
+        room: Vnum,
+        exits: u32,
+    },
+}
+
+/// Parses the `#RESETS` section, which unlike the others is closed by `S` instead of `#0`. Each
+/// reset comes with the line it was read from.
+pub fn parse_resets<'a>(text: &'a str, diagnostics: &mut Diagnostics) -> Vec<(&'a str, Reset)> {
+    let mut reader = match find_section(text, "RESETS") {
+        Some(reader) => reader,
+        None => return vec![],
+    };
+
+    let mut resets = vec![];
+    loop {
+        if matches!(reader.peek(), None | Some('#')) {
+            diagnostics.error(reader.rest(), "#RESETS section isn't closed by `S`");
+            break;
+        }
+        let line = reader.read_rest_of_line().trim();
+        if line == "S" {
+            break;
+        } else if line.starts_with('*') {
+            continue;
+        }
+        match parse_reset(line) {
+            Some(Some(reset)) => resets.push((line, reset)),
+            Some(None) => {}
+            None => diagnostics.error(line, format!("Invalid reset: {line}")),
+        }
+    }
+    resets
+}
+
+/// Returns `Some(None)` for valid resets that don't matter to areview
+fn parse_reset(line: &str) -> Option<Option<Reset>> {
+    let mut words = line.split_whitespace();
+    let command = words.next()?;
+    // Anything after the arguments is a comment, with or without a leading `*`
+    let args: Vec<&str> = words.take_while(|w| w.parse::<i64>().is_ok()).collect();
+    let vnum = |idx: usize| args.get(idx)?.parse::<Vnum>().ok();
+
+    let reset = match command {
+        "M" => Reset::Mobile {
+            vnum: vnum(1)?,
+            room: vnum(3)?,
+        },
+        "O" => Reset::Object {
+            vnum: vnum(1)?,
+            room: vnum(3)?,
+        },
+        "P" => Reset::Put {
+            vnum: vnum(1)?,
+            container: vnum(3)?,
+        },
+        "G" => Reset::Give { vnum: vnum(1)? },
+        "E" => Reset::Equip {
+            vnum: vnum(1)?,
+            wear_location: args.get(3)?.parse().ok()?,
+        },
+        "D" => Reset::Door {
+            room: vnum(1)?,
+            direction: Direction::from_str(args.get(2)?)?,
+            state: DoorState::from_reset_state(args.get(3)?)?,
+        },
+        "R" => Reset::Randomize {
+            room: vnum(1)?,
+            exits: vnum(2)?,
+        },
+        // SMAUG traps, hidden objects and flag bits don't change anything on the map
+        "T" | "H" | "B" => return Some(None),
+        _ => return None,
+    };
+    Some(Some(reset))
+}
+
+/// Works out what each reset puts where, the same way the MUD would when the area resets. `D`
+/// resets change the door state on the room's exit.
+pub fn apply_resets(
+    resets: &[(&str, Reset)],
+    rooms: &mut [Room],
+    mobiles: &[Mobile],
+    objects: &[Object],
+    diagnostics: &mut Diagnostics,
+) -> FnvHashMap<Vnum, RoomSpawns> {
+    let mut spawns: FnvHashMap<Vnum, RoomSpawns> = FnvHashMap::default();
+    // `G` and `E` resets apply to whichever mobile was loaded last
+    let mut last_mobile: Option<(Vnum, usize)> = None;
+    // `P` resets look up where their container was last loaded
+    let mut last_loaded_in: FnvHashMap<Vnum, Vnum> = FnvHashMap::default();
+
+    let room_vnums: FnvHashSet<Vnum> = rooms.iter().map(|r| r.vnum).collect();
+    let room_exists = |vnum: Vnum| room_vnums.contains(&vnum);
+    let mobile_vnums: FnvHashSet<Vnum> = mobiles.iter().map(|m| m.vnum).collect();
+    let object_vnums: FnvHashSet<Vnum> = objects.iter().map(|o| o.vnum).collect();
+    let check_mobile = |diagnostics: &mut Diagnostics, line: &str, vnum: Vnum| {
+        if !mobile_vnums.contains(&vnum) {
+            diagnostics.warning(
+                line,
+                format!("Reset loads mobile {vnum}, which isn't in this area"),
+            );
+        }
+    };
+    let check_object = |diagnostics: &mut Diagnostics, line: &str, vnum: Vnum| {
+        if !object_vnums.contains(&vnum) {
+            diagnostics.warning(
+                line,
+                format!("Reset loads object {vnum}, which isn't in this area"),
+            );
+        }
+    };
+
+    for (line, reset) in resets {
+        let line = *line;
+        match *reset {
+            Reset::Mobile { vnum, room } => {
+                check_mobile(diagnostics, line, vnum);
+                if !room_exists(room) {
+                    diagnostics.warning(
+                        line,
+                        format!("Reset loads mobile {vnum} into missing room {room}"),
+                    );
+                    last_mobile = None;
+                    continue;
+                }
+                let room_spawns = spawns.entry(room).or_default();
+                room_spawns.mobiles.push(SpawnedMobile::new(vnum));
+                last_mobile = Some((room, room_spawns.mobiles.len() - 1));
+            }
+            Reset::Object { vnum, room } => {
+                check_object(diagnostics, line, vnum);
+                if !room_exists(room) {
+                    diagnostics.warning(
+                        line,
+                        format!("Reset loads object {vnum} into missing room {room}"),
+                    );
+                    continue;
+                }
+                let room_spawns = spawns.entry(room).or_default();
+                room_spawns.objects.push(SpawnedObject::new(vnum));
+                last_loaded_in.insert(vnum, room);
+            }
+            Reset::Give { vnum } | Reset::Equip { vnum, .. } => {
+                check_object(diagnostics, line, vnum);
+                let (room, idx) = match last_mobile {
+                    Some(last) => last,
+                    None => {
+                        diagnostics.warning(
+                            line,
+                            format!("Reset gives object {vnum} to a mobile that wasn't loaded"),
+                        );
+                        continue;
+                    }
+                };
+                let mobile = &mut spawns.get_mut(&room).unwrap().mobiles[idx];
+                if let Reset::Equip { wear_location, .. } = reset {
+                    mobile
+                        .equipment
+                        .push((*wear_location, SpawnedObject::new(vnum)));
+                } else {
+                    mobile.inventory.push(SpawnedObject::new(vnum));
+                }
+                last_loaded_in.insert(vnum, room);
+            }
+            Reset::Put { vnum, container } => {
+                check_object(diagnostics, line, vnum);
+                let found = last_loaded_in.get(&container).and_then(|room| {
+                    spawns
+                        .get_mut(room)
+                        .and_then(|s| s.find_object_mut(container))
+                        .map(|object| (*room, object))
+                });
+                match found {
+                    Some((room, object)) => {
+                        object.contents.push(SpawnedObject::new(vnum));
+                        last_loaded_in.insert(vnum, room);
+                    }
+                    None => diagnostics.warning(
+                        line,
+                        format!(
+                        "Reset puts object {vnum} into container {container}, which wasn't loaded"
+                    ),
+                    ),
+                }
+            }
+            Reset::Door {
+                room,
+                direction,
+                state,
+            } => match rooms.iter_mut().find(|r| r.vnum == room) {
+                Some(r) => match r.exits.get_mut(&direction) {
+                    Some(exit) if exit.door.is_door => exit.door = exit.door.reset_to(state),
+                    Some(_) => diagnostics.warning(
+                        line,
+                        format!(
+                            "Reset closes a door {direction:?} of room {room}, but there's no door"
+                        ),
+                    ),
+                    None => diagnostics.warning(
+                        line,
+                        format!(
+                            "Reset closes a door {direction:?} of room {room}, but there's no exit"
+                        ),
+                    ),
+                },
+                None => {
+                    diagnostics.warning(line, format!("Reset closes a door in missing room {room}"))
+                }
+            },
+            Reset::Randomize { room, exits } => {
+                if !room_exists(room) {
+                    diagnostics.warning(
+                        line,
+                        format!("Reset randomizes exits of missing room {room}"),
+                    );
+                    continue;
+                }
+                spawns.entry(room).or_default().randomized_exits = Some(exits);
+            }
+        }
+    }
+
+    spawns
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_resets, parse_resets, Reset};
+    use crate::model::{Direction, Door, DoorState, Room, SpawnedObject};
+    use crate::parser::diagnostic::Diagnostics;
+
+    const RESETS: &str = "#RESETS
+* The wizard and his things
+M 0 3000 1 3001\t* the wizard
+E 1 3020 0 16\tthe sword
+G 1 3012 0
+O 0 3011 0 3001
+P 1 3012 0 3011
+D 0 3001 0 2
+D 0 3001 2 1
+R 0 3001 4
+X 0 0 0 0
+S
+";
+
+    #[test]
+    fn parses_reset_commands() {
+        let mut diagnostics = Diagnostics::new("test.are", RESETS);
+        let resets: Vec<_> = parse_resets(RESETS, &mut diagnostics)
+            .into_iter()
+            .map(|(_, reset)| reset)
+            .collect();
+        assert_eq!(
+            diagnostics.into_vec()[0].to_string(),
+            "test.are:11:1: error: Invalid reset: X 0 0 0 0"
+        );
+        assert_eq!(
+            resets,
+            vec![
+                Reset::Mobile {
+                    vnum: 3000,
+                    room: 3001
+                },
+                Reset::Equip {
+                    vnum: 3020,
+                    wear_location: 16
+                },
+                Reset::Give { vnum: 3012 },
+                Reset::Object {
+                    vnum: 3011,
+                    room: 3001
+                },
+                Reset::Put {
+                    vnum: 3012,
+                    container: 3011
+                },
+                Reset::Door {
+                    room: 3001,
+                    direction: Direction::North,
+                    state: DoorState::Locked
+                },
+                Reset::Door {
+                    room: 3001,
+                    direction: Direction::South,
+                    state: DoorState::Closed
+                },
+                Reset::Randomize {
+                    room: 3001,
+                    exits: 4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_resets_to_rooms() {
+        let mut diagnostics = Diagnostics::new("test.are", RESETS);
+        let resets = parse_resets(RESETS, &mut diagnostics);
+        let mut rooms =
+            vec![
+                Room::test(3001, &[(Direction::North, 3002), (Direction::South, 3003)])
+                    .with_door(Direction::North, Door::from_locks(1)),
+            ];
+        let spawns = apply_resets(&resets, &mut rooms, &[], &[], &mut diagnostics);
+
+        let room_spawns = spawns.get(&3001).unwrap();
+        assert_eq!(room_spawns.mobiles.len(), 1);
+        let wizard = &room_spawns.mobiles[0];
+        assert_eq!(wizard.vnum, 3000);
+        assert_eq!(wizard.equipment, vec![(16, SpawnedObject::new(3020))]);
+        assert_eq!(wizard.inventory, vec![SpawnedObject::new(3012)]);
+
+        assert_eq!(room_spawns.objects.len(), 1);
+        assert_eq!(room_spawns.objects[0].vnum, 3011);
+        assert_eq!(
+            room_spawns.objects[0].contents,
+            vec![SpawnedObject::new(3012)]
+        );
+        assert_eq!(room_spawns.randomized_exits, Some(4));
+
+        // Only the exit with a door can be locked
+        let north = rooms[0].exits[&Direction::North].door;
+        assert!(north.closed && north.locked);
+        assert_eq!(rooms[0].exits[&Direction::South].door, Door::NONE);
+
+        // The area has no mobiles or objects of its own, so each load is reported on its line
+        let diagnostics = diagnostics.into_vec();
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "test.are:3:1: warning: Reset loads mobile 3000, which isn't in this area"
+        );
+    }
+}
@@ -1,55 +1,58 @@
-use crate::model::{Direction, Door, Room, Sector, Vnum};
-use fnv::FnvHashMap;
-use once_cell::sync::Lazy;
-use regex::{Captures, Match, Regex};
+use crate::model::{
+    color_code_problems, ColorCodes, Direction, ExtraDescription, Room, RoomExit, RoomFlags,
+    Sector, SectorTable, Vnum,
+};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::dialect::AreaDialect;
+use crate::parser::reader::{AreaReader, ParseError};
+use crate::parser::section::{find_section, read_entries};
+use fnv::{FnvHashMap, FnvHashSet};
+use std::collections::hash_map::Entry;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::str::FromStr;
 
-pub fn parse_rooms(text: &str) -> Result<Vec<Room>, Box<dyn Error>> {
-    let room_section_regex = Regex::new(r"(?ims)^#ROOMS\s*$(.*?)^#0\s*$").unwrap();
-
-    // TODO: write a PR to regex to let String be indexed by match
-    let room_section = room_section_regex.captures(&text).ok_or(NoRoomsSection)?;
-
-    let section_match = room_section.get(1).unwrap();
-    let section_text = &text[section_match.start()..section_match.end()];
-
-    let room_split_regex = Regex::new(r"(?m)^#(\d+)").unwrap();
-    let room_matches: Vec<Match> = room_split_regex.find_iter(section_text).collect();
-    let mut rooms: Vec<Room> = vec![];
-    if room_matches.is_empty() {
+/// Reads the fields in the line after a room's description, returning its flags and the word
+/// holding its sector number
+pub type RoomFlagsLine = for<'w> fn(&[&'w str]) -> Option<(RoomFlags, &'w str)>;
+
+pub fn parse_rooms<D: AreaDialect + ?Sized>(
+    text: &str,
+    dialect: &D,
+    sectors: &SectorTable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<Room>, Box<dyn Error>> {
+    let mut reader = find_section(text, "ROOMS").ok_or(NoRoomsSection)?;
+
+    let entries = read_entries(&mut reader, diagnostics, |reader, vnum, diagnostics| {
+        read_room(reader, vnum, dialect, sectors, flags_line, diagnostics)
+    });
+    if entries.is_empty() {
         return Err(Box::new(NoRooms));
     }
 
-    for matches in room_matches.windows(2) {
-        let m = matches.get(0).unwrap();
-        let next = matches.get(1).unwrap();
+    Ok(dedup_rooms(entries, diagnostics))
+}
 
-        match parse_room(section_text, m.start(), m.end(), next.start()) {
-            Ok(room) => {
-                if rooms.iter().any(|r| r.vnum == room.vnum) {
-                    eprintln!("Duplicate VNUM {}", room.vnum);
-                } else {
-                    rooms.push(room);
-                }
-            }
-            Err(e) => eprintln!("{e}"),
-        }
+/// Merc writes `area flags sector`, SMAUG may add teleport fields after those
+fn flags_line<'w>(words: &[&'w str]) -> Option<(RoomFlags, &'w str)> {
+    match words {
+        [_, flags, sector, ..] => Some((RoomFlags::from_str(flags)?, sector)),
+        _ => None,
     }
+}
 
-    let last_match = room_matches.iter().last().unwrap();
-    match parse_room(
-        section_text,
-        last_match.start(),
-        last_match.end(),
-        section_text.len(),
-    ) {
-        Ok(room) => rooms.push(room),
-        Err(e) => eprintln!("{e}"),
+/// Keeps the first of any rooms that share a vnum
+pub fn dedup_rooms(entries: Vec<(&str, Room)>, diagnostics: &mut Diagnostics) -> Vec<Room> {
+    let mut rooms: Vec<Room> = vec![];
+    let mut seen = FnvHashSet::default();
+    for (vnum_text, room) in entries {
+        if !seen.insert(room.vnum) {
+            diagnostics.warning(vnum_text, format!("Duplicate VNUM {}", room.vnum));
+        } else {
+            rooms.push(room);
+        }
     }
-
-    Ok(rooms)
+    rooms
 }
 
 #[derive(Debug)]
@@ -74,126 +77,312 @@ impl Display for NoRooms {
 
 impl Error for NoRooms {}
 
-static ROOM_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"(?mx)\A\s*
-        (?P<name>[^~]*)~
-        [^~]*~\s*
-        \d+\s+(?P<flags>[\d|]+)\s+(?P<sector>\d+)\s*",
-    )
-    .unwrap()
-});
-
-fn parse_room<'a>(
-    text: &'a str,
-    vnum_start: usize,
-    vnum_end: usize,
-    text_end: usize,
-) -> Result<Room, Box<dyn Error + 'a>> {
-    let vnum_text = &text[vnum_start + 1..vnum_end];
-    let vnum = u32::from_str(vnum_text)?;
-    let room_body = &text[vnum_end..text_end];
-    let captures = ROOM_REGEX
-        .captures(room_body)
-        .ok_or(InvalidRoomBody { body: room_body })?;
-
-    let name_match = captures.name("name").unwrap();
-    let name = room_body[name_match.start()..name_match.end()].to_string();
-
-    let sector_match = captures.name("sector").unwrap();
-    let sector = Sector::from_str(&room_body[sector_match.start()..sector_match.end()]);
-
-    let captures_match = captures.get(0).unwrap();
-    let exits = parse_doors(vnum, &room_body[captures_match.end()..]);
+/// Reads one room, from its name up to the `S` that closes it
+pub fn read_room<'a, D: AreaDialect + ?Sized>(
+    reader: &mut AreaReader<'a>,
+    vnum: Vnum,
+    dialect: &D,
+    sectors: &SectorTable,
+    flags_line: RoomFlagsLine,
+    diagnostics: &mut Diagnostics,
+) -> Result<Room, ParseError<'a>> {
+    let name = reader.read_string()?;
+    let description = reader.read_string()?;
+    check_color_codes(name, vnum, dialect.color_codes(), diagnostics);
+    check_color_codes(description, vnum, dialect.color_codes(), diagnostics);
+
+    let line = reader.read_line()?;
+    let words: Vec<_> = line.split_whitespace().collect();
+    let (flags, sector_number) = flags_line(&words)
+        .ok_or_else(|| ParseError::new(line, format!("Invalid flags line for room {vnum}")))?;
+    // The config file can claim numbers the dialect uses for something else
+    let sector = sectors
+        .sector(sector_number)
+        .or_else(|| dialect.sector(sector_number))
+        .unwrap_or_else(|| {
+            diagnostics.warning(
+                sector_number,
+                format!("Unknown sector {sector_number} in room {vnum}"),
+            );
+            Sector::Inside
+        });
+
+    let mut exits = FnvHashMap::default();
+    let mut extra_descriptions = vec![];
+    loop {
+        if matches!(reader.peek(), None | Some('#') | Some('$')) {
+            return Err(ParseError::new(
+                reader.rest(),
+                format!("Room {vnum} isn't closed by `S`"),
+            ));
+        }
+        let word = reader.read_word()?;
+        match word {
+            "S" => break,
+            "E" => extra_descriptions.push(read_extra_description(reader)?),
+            _ if word.starts_with(['D', 'd']) => {
+                let (direction, exit) = read_exit(reader, word, dialect)?;
+                if let Entry::Vacant(e) = exits.entry(direction) {
+                    e.insert(exit);
+                } else {
+                    diagnostics.warning(
+                        word,
+                        format!("Duplicate exit direction in room {vnum}: {direction:?}"),
+                    );
+                }
+            }
+            // ROM's heal and mana rates, clans and owners, SMAUG's programs and the like
+            _ => reader.skip_line(word)?,
+        }
+    }
 
     Ok(Room {
-        name,
+        name: name.to_string(),
         vnum,
         string_vnum: vnum.to_string(),
+        description: description.to_string(),
+        flags,
+        extra_descriptions,
         exits,
         sector,
     })
 }
 
-#[derive(Debug)]
-struct InvalidRoomBody<'a> {
-    body: &'a str,
-}
-
-impl<'a> Display for InvalidRoomBody<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Invalid room body:\n{}", self.body)
+fn check_color_codes(text: &str, vnum: Vnum, codes: ColorCodes, diagnostics: &mut Diagnostics) {
+    for (offset, problem) in color_code_problems(text, codes) {
+        diagnostics.warning(&text[offset..], format!("{problem} in room {vnum}"));
     }
 }
 
-impl<'a> Error for InvalidRoomBody<'a> {}
-
-static DOOR_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"(?imsx)
-        ^D(?P<direction>\d+)
-        [^~]*~
-        [^~]*~\s*
-        (?P<locks>\d+)\s+\S+\s+(?P<destination>\d+)",
-    )
-    .unwrap()
-});
-
-fn parse_doors(room_vnum: Vnum, text: &str) -> FnvHashMap<Direction, (Vnum, Door)> {
-    let mut exits = FnvHashMap::default();
-
-    for captures in DOOR_REGEX.captures_iter(text) {
-        match parse_door(text, captures) {
-            Ok((direction, destination)) => {
-                if exits.contains_key(&direction) {
-                    eprintln!(
-                        "Duplicate exit direction in room {}: {:?}",
-                        room_vnum, direction
-                    );
-                } else {
-                    exits.insert(direction, destination);
-                }
-            }
-            Err(e) => eprintln!("{e}"),
-        }
-    }
-
-    exits
+/// Reads the keywords and text that follow an `E`
+pub fn read_extra_description<'a>(
+    reader: &mut AreaReader<'a>,
+) -> Result<ExtraDescription, ParseError<'a>> {
+    Ok(ExtraDescription {
+        keywords: reader.read_string()?.to_string(),
+        description: reader.read_string()?.to_string(),
+    })
 }
 
-fn parse_door<'a>(
-    text: &'a str,
-    captures: Captures,
-) -> Result<(Direction, (Vnum, Door)), Box<dyn Error + 'a>> {
-    let direction_match = captures.name("direction").unwrap();
-    let direction = match &text[direction_match.start()..direction_match.end()] {
-        "0" => Direction::North,
-        "1" => Direction::East,
-        "2" => Direction::South,
-        "3" => Direction::West,
-        "4" => Direction::Up,
-        "5" => Direction::Down,
-        dir => return Err(Box::new(InvalidDirection(dir))),
+/// Reads an exit, given the `D` word that started it. The direction usually follows the `D`
+/// straight away, but may be written as a separate word.
+fn read_exit<'a, D: AreaDialect + ?Sized>(
+    reader: &mut AreaReader<'a>,
+    word: &'a str,
+    dialect: &D,
+) -> Result<(Direction, RoomExit), ParseError<'a>> {
+    let direction_text = match &word[1..] {
+        "" => reader.read_word()?,
+        _ => &word[1..],
     };
-    let destination_match = captures.name("destination").unwrap();
-    let destination = u32::from_str(&text[destination_match.start()..destination_match.end()])?;
+    let direction = dialect.direction(direction_text).ok_or_else(|| {
+        ParseError::new(
+            direction_text,
+            format!("Invalid direction `{direction_text}`"),
+        )
+    })?;
+
+    let description = reader.read_string()?;
+    let keywords = reader.read_string()?;
+    let locks = reader.read_flags("exit locks")?;
+    // Zero and -1 both mean there's no key
+    let key = reader.read_number::<i64>("a key vnum")?;
+    let destination = reader.read_number("a destination vnum")?;
+    reader.read_rest_of_line();
+
+    Ok((
+        direction,
+        RoomExit {
+            destination,
+            door: dialect.door(locks),
+            keywords: keywords.to_string(),
+            description: description.to_string(),
+            key: if key > 0 { Some(key as Vnum) } else { None },
+        },
+    ))
+}
 
-    let locks_match = captures.name("locks").unwrap();
-    let door = match &text[locks_match.start()..locks_match.end()] {
-        "0" => Door::None,
-        _ => Door::Closed,
-    };
+#[cfg(test)]
+mod test {
+    use super::parse_rooms;
+    use crate::model::{Direction, Door, RoomFlag, Sector, SectorTable};
+    use crate::parser::diagnostic::Diagnostics;
+    use crate::parser::dialect::Avatar;
+
+    const AREA: &str = "#ROOMS
+#1000
+The Temple~
+A vast temple.
+Candles flicker along the walls.
+~
+0 8|1024 0
+D0
+A heavy oak door leads north.
+~
+door oak~
+2 1005 1001
+E
+altar~
+A plain stone altar.
+~
+E
+candles wall~
+They smell of tallow.
+~
+S
+#1001
+A Dark Alley~
+~
+0 1 1
+D2
+~
+door~
+0 0 1000
+S
+#0
+";
+
+    #[test]
+    fn parses_descriptions_and_flags() {
+        let rooms = parse_rooms(
+            AREA,
+            &Avatar,
+            &SectorTable::default(),
+            &mut Diagnostics::new("test.are", AREA),
+        )
+        .unwrap();
+        assert_eq!(rooms.len(), 2);
+
+        let temple = &rooms[0];
+        assert_eq!(temple.name, "The Temple");
+        assert_eq!(
+            temple.description,
+            "A vast temple.\nCandles flicker along the walls.\n"
+        );
+        assert_eq!(
+            temple.flags.iter().collect::<Vec<_>>(),
+            vec![RoomFlag::Indoors, RoomFlag::Safe]
+        );
+        assert_eq!(temple.sector, Sector::Inside);
+        let north = &temple.exits[&Direction::North];
+        assert_eq!(north.destination, 1001);
+        assert_eq!(north.description, "A heavy oak door leads north.\n");
+        assert_eq!(north.keywords, "door oak");
+        assert_eq!(north.key, Some(1005));
+        assert!(north.door.is_door && north.door.pickproof);
+
+        let alley = &rooms[1];
+        assert_eq!(alley.description, "");
+        assert!(alley.flags.contains(RoomFlag::Dark));
+        assert_eq!(alley.sector, Sector::City);
+        assert_eq!(alley.exits[&Direction::South].door, Door::NONE);
+        assert_eq!(alley.exits[&Direction::South].key, None);
+    }
 
-    Ok((direction, (destination, door)))
-}
+    #[test]
+    fn parses_extra_descriptions() {
+        let rooms = parse_rooms(
+            AREA,
+            &Avatar,
+            &SectorTable::default(),
+            &mut Diagnostics::new("test.are", AREA),
+        )
+        .unwrap();
+
+        let extras: Vec<_> = rooms[0]
+            .extra_descriptions
+            .iter()
+            .map(|e| (e.keywords.as_str(), e.description.as_str()))
+            .collect();
+        assert_eq!(
+            extras,
+            vec![
+                ("altar", "A plain stone altar.\n"),
+                ("candles wall", "They smell of tallow.\n"),
+            ]
+        );
+        assert!(rooms[1].extra_descriptions.is_empty());
+    }
 
-#[derive(Debug)]
-struct InvalidDirection<'a>(&'a str);
+    #[test]
+    fn reads_hashes_in_descriptions() {
+        let area = "#ROOMS
+#1000
+The Temple~
+Carved into the altar:
+#1001 was here
+~
+0 0 0
+S
+#0
+";
+        let rooms = parse_rooms(
+            area,
+            &Avatar,
+            &SectorTable::default(),
+            &mut Diagnostics::new("test.are", area),
+        )
+        .unwrap();
+        assert_eq!(rooms.len(), 1);
+        assert_eq!(
+            rooms[0].description,
+            "Carved into the altar:\n#1001 was here\n"
+        );
+    }
 
-impl<'a> Display for InvalidDirection<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Invalid direction: {}", self.0)
+    #[test]
+    fn reports_problems_with_their_position() {
+        let area = "#ROOMS
+#1000
+The Temple~
+~
+0 0 0
+D0
+~
+~
+0 0 1001
+D0
+~
+~
+0 0 1002
+S
+#1000
+Another Temple~
+~
+0 0 0
+S
+#1001
+A Broken Room~
+A stray ~ in the description
+~
+0 0 0
+S
+#1002
+The {qRoom After~
+~
+0 0 42
+S
+#0
+";
+        let mut diagnostics = Diagnostics::new("test.are", area);
+        let rooms = parse_rooms(area, &Avatar, &SectorTable::default(), &mut diagnostics).unwrap();
+        let vnums: Vec<_> = rooms.iter().map(|r| r.vnum).collect();
+        assert_eq!(vnums, vec![1000, 1002]);
+
+        let messages: Vec<_> = diagnostics
+            .into_vec()
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "test.are:10:1: warning: Duplicate exit direction in room 1000: North",
+                "test.are:15:2: warning: Duplicate VNUM 1000",
+                "test.are:22:11: error: Invalid flags line for room 1001",
+                "test.are:27:5: warning: Unknown color code `{q` in room 1002",
+                "test.are:29:5: warning: Unknown sector 42 in room 1002",
+            ]
+        );
     }
 }
-
-impl<'a> Error for InvalidDirection<'a> {}
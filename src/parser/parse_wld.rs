@@ -0,0 +1,159 @@
+use crate::model::{Room, RoomFlag, RoomFlags, SectorTable};
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::dialect::AreaDialect;
+use crate::parser::parse_rooms::{dedup_rooms, read_room};
+use crate::parser::reader::AreaReader;
+use crate::parser::section::read_entries;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Parses a CircleMUD or tbaMUD world file. These hold nothing but rooms, with no section
+/// headers, each room closed by `S` and the file closed by `$`.
+pub fn parse_wld<D: AreaDialect + ?Sized>(
+    text: &str,
+    dialect: &D,
+    sectors: &SectorTable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<Room>, Box<dyn Error>> {
+    let entries = read_entries(
+        &mut AreaReader::new(text),
+        diagnostics,
+        |reader, vnum, diagnostics| {
+            read_room(reader, vnum, dialect, sectors, flags_line, diagnostics)
+        },
+    );
+    if entries.is_empty() {
+        return Err(Box::new(NoRoomsInWorld));
+    }
+
+    Ok(dedup_rooms(entries, diagnostics))
+}
+
+/// Circle writes `zone flags sector`, tbaMUD splits the flags over four fields
+fn flags_line<'w>(words: &[&'w str]) -> Option<(RoomFlags, &'w str)> {
+    let (flags, sector) = match words {
+        [_, flags, _, _, _, sector, ..] => (flags, sector),
+        [_, flags, sector, ..] => (flags, sector),
+        _ => return None,
+    };
+    Some((circle_room_flags(flags)?, sector))
+}
+
+/// Circle flags are either a number or letters where `a` is the lowest bit. Only the flags that
+/// mean the same thing as a [RoomFlag] are kept, the rest don't line up with Merc's bits.
+fn circle_room_flags(s: &str) -> Option<RoomFlags> {
+    let bits = if s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse::<u32>().ok()?
+    } else {
+        let mut bits = 0u32;
+        for letter in s.chars() {
+            bits |= match letter {
+                'a'..='z' => 1 << (letter as u32 - 'a' as u32),
+                'A'..='F' => 1 << (letter as u32 - 'A' as u32 + 26),
+                _ => return None,
+            };
+        }
+        bits
+    };
+
+    const CIRCLE_FLAGS: &[(u32, RoomFlag)] = &[
+        (1 << 0, RoomFlag::Dark),
+        (1 << 1, RoomFlag::Death),
+        (1 << 2, RoomFlag::NoMob),
+        (1 << 3, RoomFlag::Indoors),
+        (1 << 4, RoomFlag::Safe),
+        (1 << 9, RoomFlag::Private),
+    ];
+    let mut flags = RoomFlags::default();
+    for (bit, flag) in CIRCLE_FLAGS {
+        if bits & bit != 0 {
+            flags.0 |= flag.bit();
+        }
+    }
+    Some(flags)
+}
+
+#[derive(Debug)]
+struct NoRoomsInWorld;
+
+impl Display for NoRoomsInWorld {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No rooms in world file")
+    }
+}
+
+impl Error for NoRoomsInWorld {}
+
+#[cfg(test)]
+mod test {
+    use super::parse_wld;
+    use crate::model::{Direction, Door, RoomFlag, Sector, SectorTable};
+    use crate::parser::diagnostic::Diagnostics;
+    use crate::parser::dialect::Circle;
+
+    const WORLD: &str = "#18600
+The Entrance~
+   You are standing in the entrance of the haunted house.
+~
+186 ad 0
+D0
+The hallway continues north.
+~
+door~
+1 18610 18601
+E
+sign~
+It says 'Keep out'.
+~
+S
+#18601
+The Hallway~
+   A dusty hallway.
+~
+186 0 0 0 0 8
+D2
+~
+~
+0 -1 18600
+D4
+~
+~
+0 -1 18699
+S
+$~
+";
+
+    #[test]
+    fn parses_world_rooms() {
+        let rooms = parse_wld(
+            WORLD,
+            &Circle,
+            &SectorTable::default(),
+            &mut Diagnostics::new("test.wld", WORLD),
+        )
+        .unwrap();
+        assert_eq!(rooms.len(), 2);
+
+        let entrance = &rooms[0];
+        assert_eq!(entrance.vnum, 18600);
+        assert_eq!(entrance.name, "The Entrance");
+        assert_eq!(
+            entrance.flags.iter().collect::<Vec<_>>(),
+            vec![RoomFlag::Dark, RoomFlag::Indoors]
+        );
+        assert_eq!(entrance.sector, Sector::Inside);
+        let north = &entrance.exits[&Direction::North];
+        assert_eq!(north.destination, 18601);
+        assert_eq!(north.door, Door::from_locks(1));
+        assert_eq!(north.keywords, "door");
+        assert_eq!(north.description, "The hallway continues north.\n");
+        assert_eq!(north.key, Some(18610));
+        assert_eq!(entrance.extra_descriptions[0].keywords, "sign");
+
+        let hallway = &rooms[1];
+        assert_eq!(hallway.sector, Sector::Air);
+        assert_eq!(hallway.exits[&Direction::South].destination, 18600);
+        assert_eq!(hallway.exits[&Direction::Up].door, Door::NONE);
+        assert_eq!(hallway.exits[&Direction::Up].key, None);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::model::parse_flags;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Reads area text the way the MUDs themselves do, one tilde-terminated string, word or number at
+/// a time. Everything it hands out is a slice of the original text, so problems can be pointed at.
+pub struct AreaReader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> AreaReader<'a> {
+    pub fn new(text: &'a str) -> Self {
+        AreaReader { text, pos: 0 }
+    }
+
+    /// The text that hasn't been read yet
+    #[inline]
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// The next character that isn't whitespace, without reading it
+    pub fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    /// Reads `expected` if it's the next character that isn't whitespace
+    pub fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reads up to the next `~`, skipping leading whitespace like `fread_string` does
+    pub fn read_string(&mut self) -> Result<&'a str, ParseError<'a>> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let end = rest
+            .find('~')
+            .ok_or_else(|| ParseError::new(rest, "Unterminated string, expected a `~`"))?;
+        self.pos += end + 1;
+        Ok(&rest[..end])
+    }
+
+    /// Reads everything up to the next whitespace
+    pub fn read_word(&mut self) -> Result<&'a str, ParseError<'a>> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if rest.is_empty() {
+            return Err(ParseError::new(rest, "Unexpected end of file"));
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        self.pos += end;
+        Ok(&rest[..end])
+    }
+
+    /// Reads a word and parses it, calling it `what` if that fails
+    pub fn read_number<T: FromStr>(&mut self, what: &str) -> Result<T, ParseError<'a>> {
+        let word = self.read_word()?;
+        word.parse()
+            .map_err(|_| ParseError::new(word, format!("Expected {what}, found `{word}`")))
+    }
+
+    /// Reads a flag field, in any of the forms [parse_flags] understands
+    pub fn read_flags(&mut self, what: &str) -> Result<u32, ParseError<'a>> {
+        let word = self.read_word()?;
+        parse_flags(word).ok_or_else(|| ParseError::new(word, format!("Invalid {what} `{word}`")))
+    }
+
+    /// Reads the rest of the current line, and moves on to the start of the next
+    pub fn read_rest_of_line(&mut self) -> &'a str {
+        let rest = self.rest();
+        let (line, len) = match rest.find('\n') {
+            Some(end) => (&rest[..end], end + 1),
+            None => (rest, rest.len()),
+        };
+        self.pos += len;
+        line.trim_end_matches('\r')
+    }
+
+    /// Reads the next line that isn't blank
+    pub fn read_line(&mut self) -> Result<&'a str, ParseError<'a>> {
+        self.skip_whitespace();
+        if self.rest().is_empty() {
+            return Err(ParseError::new(self.rest(), "Unexpected end of file"));
+        }
+        Ok(self.read_rest_of_line())
+    }
+
+    /// Skips the rest of a line areview doesn't care about, which started with `word`. Mob
+    /// programs carry on past the end of their line with a string of commands.
+    pub fn skip_line(&mut self, word: &str) -> Result<(), ParseError<'a>> {
+        self.read_rest_of_line();
+        if word.starts_with('>') {
+            self.read_string()?;
+        }
+        Ok(())
+    }
+
+    /// Skips whatever is left of an entry, up to the `#` that starts the next one
+    pub fn skip_entry(&mut self) -> Result<(), ParseError<'a>> {
+        while !matches!(self.peek(), None | Some('#') | Some('$')) {
+            let word = self.read_word()?;
+            self.skip_line(word)?;
+        }
+        Ok(())
+    }
+
+    /// Gets past a broken entry by skipping lines until one starts with a `#`
+    pub fn recover(&mut self) {
+        loop {
+            self.read_rest_of_line();
+            let rest = self.rest();
+            if rest.is_empty() || rest.starts_with('#') || rest.starts_with('$') {
+                return;
+            }
+        }
+    }
+}
+
+/// Where and why reading an area went wrong
+#[derive(Debug)]
+pub struct ParseError<'a> {
+    pub at: &'a str,
+    pub message: String,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(at: &'a str, message: impl Into<String>) -> Self {
+        ParseError {
+            at,
+            message: message.into(),
+        }
+    }
+}
+
+impl<'a> Display for ParseError<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl<'a> Error for ParseError<'a> {}
+
+#[cfg(test)]
+mod test {
+    use super::AreaReader;
+
+    #[test]
+    fn reads_strings_words_and_numbers() {
+        let text = "The Temple~\n#1 is a fine\nroom.\n~\n0 8|1024 -3\nD0\n";
+        let mut reader = AreaReader::new(text);
+        assert_eq!(reader.read_string().unwrap(), "The Temple");
+        assert_eq!(reader.read_string().unwrap(), "#1 is a fine\nroom.\n");
+        assert_eq!(reader.read_number::<u32>("a number").unwrap(), 0);
+        assert_eq!(reader.read_flags("room flags").unwrap(), 1032);
+        assert_eq!(reader.read_number::<i32>("a number").unwrap(), -3);
+        assert_eq!(reader.peek(), Some('D'));
+        assert_eq!(reader.read_word().unwrap(), "D0");
+        assert!(reader.read_word().is_err());
+    }
+
+    #[test]
+    fn points_at_what_went_wrong() {
+        let text = "name~\n12 x4 3\n";
+        let mut reader = AreaReader::new(text);
+        reader.read_string().unwrap();
+        reader.read_word().unwrap();
+
+        let error = reader.read_number::<u32>("a sector").unwrap_err();
+        assert_eq!(error.message, "Expected a sector, found `x4`");
+        assert!(std::ptr::eq(error.at, &text[9..11]));
+
+        assert!(AreaReader::new("no tilde").read_string().is_err());
+    }
+
+    #[test]
+    fn skips_to_the_next_entry() {
+        let text = "0 0\n> greet_prog 100~\nsay #hello\n~\n|\n#3001\n";
+        let mut reader = AreaReader::new(text);
+        reader.skip_entry().unwrap();
+        assert_eq!(reader.rest(), "#3001\n");
+
+        let mut reader = AreaReader::new("garbage\nmore\n#3001\n");
+        reader.recover();
+        assert_eq!(reader.rest(), "#3001\n");
+    }
+}
@@ -15,7 +15,7 @@ impl TryFrom<&str> for Rule {
             match a {
                 "isolate" => u32::from_str(b)
                     .map_err(|_| ParseRuleError::InvalidVnum)
-                    .map(|vnum| Rule::Isolate(vnum)),
+                    .map(Rule::Isolate),
                 "separate" => match b.split_once(',') {
                     Some((v1, v2)) => {
                         let v1 = u32::from_str(v1).map_err(|_| ParseRuleError::InvalidVnum)?;
@@ -0,0 +1,83 @@
+use crate::model::Vnum;
+use crate::parser::diagnostic::Diagnostics;
+use crate::parser::reader::{AreaReader, ParseError};
+
+/// Finds the line holding a `#NAME` section header, returning a reader that starts just after it
+pub fn find_section<'a>(text: &'a str, name: &str) -> Option<AreaReader<'a>> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        offset += line.len();
+        let header = line.trim_end();
+        if header.len() == name.len() + 1
+            && header.starts_with('#')
+            && header[1..].eq_ignore_ascii_case(name)
+        {
+            return Some(AreaReader::new(&text[offset..]));
+        }
+    }
+    None
+}
+
+/// Reads `#VNUM` entries with `read_entry` until the `#0` that closes a section, or the `$` that
+/// closes a world file. Each entry comes with the text of its vnum. Broken entries are reported
+/// and skipped, so one bad entry doesn't take the rest of the section with it.
+pub fn read_entries<'a, T>(
+    reader: &mut AreaReader<'a>,
+    diagnostics: &mut Diagnostics,
+    mut read_entry: impl FnMut(&mut AreaReader<'a>, Vnum, &mut Diagnostics) -> Result<T, ParseError<'a>>,
+) -> Vec<(&'a str, T)> {
+    let mut entries = vec![];
+    loop {
+        match reader.peek() {
+            None | Some('$') => break,
+            Some('#') => {}
+            Some(_) => {
+                let word = reader.read_word().unwrap_or_default();
+                diagnostics.error(word, format!("Expected `#VNUM`, found `{word}`"));
+                reader.recover();
+                continue;
+            }
+        }
+
+        let at = reader.rest();
+        reader.eat('#');
+        let vnum_text = reader.read_word().unwrap_or_default();
+        let vnum = match vnum_text.parse::<Vnum>() {
+            Ok(0) => break,
+            Ok(vnum) => vnum,
+            Err(_) => {
+                diagnostics.error(at, "Section isn't closed by `#0`");
+                break;
+            }
+        };
+
+        match read_entry(reader, vnum, diagnostics) {
+            Ok(entry) => entries.push((vnum_text, entry)),
+            Err(e) => {
+                diagnostics.error(e.at, e);
+                reader.recover();
+            }
+        }
+    }
+    entries
+}
+
+/// Finds the `#VNUM` line that starts an entry of the `#NAME` section, pointing at the vnum
+/// itself. Files without that section, like world files, are searched from the top.
+pub fn find_entry<'a>(text: &'a str, name: &str, vnum: Vnum) -> Option<&'a str> {
+    let text = match find_section(text, name) {
+        Some(reader) => reader.rest(),
+        None => text,
+    };
+    let vnum_text = vnum.to_string();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(rest) = line.strip_prefix('#') {
+            if rest.trim_end() == vnum_text {
+                return Some(&text[offset + 1..offset + 1 + vnum_text.len()]);
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
@@ -3,6 +3,9 @@ use crate::parser::rule::Rule;
 use fnv::FnvHashMap;
 use std::rc::Rc;
 
+/// How far a corridor is stretched looking for a free spot, before giving up and splitting
+const MAX_STRETCH: i32 = 8;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Location {
     pub x: i32,
@@ -10,37 +13,82 @@ pub struct Location {
     pub room: Rc<Room>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Rect {
-    x1: i32,
-    x2: i32,
-    y1: i32,
-    y2: i32,
+impl Location {
+    /// A room with open exits at `(x, y)`, as the tests need it
+    #[cfg(test)]
+    pub fn test(vnum: Vnum, (x, y): (i32, i32), exits: &[(Direction, Vnum)]) -> Self {
+        Location {
+            x,
+            y,
+            room: Rc::new(Room::test(vnum, exits)),
+        }
+    }
+}
+
+pub type RoomsByVnum = FnvHashMap<Vnum, (Rc<Room>, usize)>;
+
+/// The rooms by vnum, numbered in the order they're given, as the tests need them
+#[cfg(test)]
+pub fn rooms_by_vnum(rooms: &[Rc<Room>]) -> RoomsByVnum {
+    rooms
+        .iter()
+        .enumerate()
+        .map(|(idx, room)| (room.vnum, (room.clone(), idx)))
+        .collect()
+}
+
+/// What to do with a room whose exits put it where another room already is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CollisionFix {
+    /// Leave it on top of the other room, and report it
+    #[default]
+    Report,
+    /// Start a new plane from the room
+    Split,
+    /// Move the room further along the exit, making the corridor to it longer
+    Stretch,
+}
+
+/// How rooms are laid out in planes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    pub rules: Vec<Rule>,
+    pub collisions: CollisionFix,
+}
+
+/// A room that was put on the same spot as another one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub room: Vnum,
+    pub other: Vnum,
 }
 
 pub fn sort_rooms(
     rooms: Vec<Rc<Room>>,
-    rules: Vec<Rule>,
-) -> (FnvHashMap<Vnum, (Rc<Room>, usize)>, Vec<Vec<Location>>) {
+    layout: &Layout,
+) -> (RoomsByVnum, Vec<Vec<Location>>, Vec<Collision>) {
     let mut rooms = rooms.clone();
-    rooms.sort_by(|a, b| a.vnum.cmp(&b.vnum));
-    let by_vnum: FnvHashMap<Vnum, (Rc<Room>, usize)> = rooms
+    rooms.sort_by_key(|r| r.vnum);
+    let by_vnum: RoomsByVnum = rooms
         .iter()
         .cloned()
         .enumerate()
         .map(|(idx, room)| (room.vnum, (room, idx)))
         .collect();
 
-    let planes = find_rooms_in_plane(None, &mut rooms, &rules);
-    (by_vnum, planes)
+    let mut collisions = vec![];
+    let planes = find_rooms_in_plane(None, &mut rooms, layout, &mut collisions);
+    (by_vnum, planes, collisions)
 }
 
 fn find_rooms_in_plane(
     location: Option<Location>,
     left_to_visit: &mut Vec<Rc<Room>>,
-    rules: &[Rule],
+    layout: &Layout,
+    collisions: &mut Vec<Collision>,
 ) -> Vec<Vec<Location>> {
     let mut this_plane = vec![];
+    let mut occupied: FnvHashMap<(i32, i32), Vnum> = FnvHashMap::default();
 
     // Function should not be called with no rooms left to visit
     if left_to_visit.is_empty() {
@@ -48,18 +96,21 @@ fn find_rooms_in_plane(
     }
 
     let mut queue = std::collections::VecDeque::with_capacity(left_to_visit.len());
-    queue.push_back(location.unwrap_or(Location {
+    let start = location.unwrap_or(Location {
         x: 0,
         y: 0,
         room: left_to_visit[0].clone(),
-    }));
+    });
+    occupied.insert((start.x, start.y), start.room.vnum);
+    queue.push_back(start);
     let mut queue_for_different_plane = vec![];
 
     while !queue.is_empty() {
         let location = queue.pop_front().unwrap();
         this_plane.push(location.clone());
 
-        for (dir, (dest, _)) in &location.room.exits {
+        for (dir, exit) in &location.room.exits {
+            let dest = &exit.destination;
             // Find the connected room
             if let Some(dest_room) = left_to_visit.iter().find(|r| r.vnum == *dest).cloned() {
                 // Should not be possible unless `left_to_visit` contained duplicate VNUMs
@@ -74,13 +125,13 @@ fn find_rooms_in_plane(
                 if !dest_room
                     .exits
                     .values()
-                    .any(|(vnum, _)| *vnum == location.room.vnum)
+                    .any(|e| e.destination == location.room.vnum)
                 {
                     queue_for_different_plane.push(dest_room);
                     continue;
                 }
 
-                if rules.iter().any(|rule| match rule {
+                if layout.rules.iter().any(|rule| match rule {
                     Rule::Isolate(vnum) if *vnum == location.room.vnum => true,
                     Rule::Separate(vnum, other) if *vnum == location.room.vnum && other == dest => {
                         true
@@ -95,17 +146,47 @@ fn find_rooms_in_plane(
                 }
 
                 match dir {
-                    Direction::Up | Direction::Down => {
+                    Direction::Up | Direction::Down | Direction::In | Direction::Out => {
                         queue_for_different_plane.push(dest_room);
                     }
                     _ => {
-                        let (x, y) = match dir {
+                        let (dx, dy) = match dir {
                             Direction::North => (0, 1),
                             Direction::East => (1, 0),
                             Direction::South => (0, -1),
                             Direction::West => (-1, 0),
+                            Direction::Northeast => (1, 1),
+                            Direction::Northwest => (-1, 1),
+                            Direction::Southeast => (1, -1),
+                            Direction::Southwest => (-1, -1),
                             _ => unreachable!(),
                         };
+                        let mut cell = (location.x + dx, location.y + dy);
+                        if let Some(&other) = occupied.get(&cell) {
+                            match layout.collisions {
+                                CollisionFix::Report => collisions.push(Collision {
+                                    room: dest_room.vnum,
+                                    other,
+                                }),
+                                CollisionFix::Split => {
+                                    queue_for_different_plane.push(dest_room);
+                                    continue;
+                                }
+                                CollisionFix::Stretch => {
+                                    let free = (2..=MAX_STRETCH)
+                                        .map(|n| (location.x + dx * n, location.y + dy * n))
+                                        .find(|cell| !occupied.contains_key(cell));
+                                    match free {
+                                        Some(free) => cell = free,
+                                        None => {
+                                            queue_for_different_plane.push(dest_room);
+                                            continue;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        occupied.entry(cell).or_insert(dest_room.vnum);
                         // If room was queued as an up/down connection for a separate plane,
                         // remove that reference and queue it for this plane instead
                         if let Some(idx) = queue_for_different_plane
@@ -115,8 +196,8 @@ fn find_rooms_in_plane(
                             queue_for_different_plane.remove(idx);
                         }
                         queue.push_back(Location {
-                            x: location.x + x,
-                            y: location.y + y,
+                            x: cell.0,
+                            y: cell.1,
                             room: dest_room,
                         });
                     }
@@ -130,13 +211,21 @@ fn find_rooms_in_plane(
 
     let mut planes = vec![this_plane];
     for room in queue_for_different_plane.into_iter() {
-        let mut more_planes =
-            find_rooms_in_plane(Some(Location { room, x: 0, y: 0 }), left_to_visit, rules);
+        // A room can be queued more than once, and found by an earlier plane in the meantime
+        if !left_to_visit.contains(&room) {
+            continue;
+        }
+        let mut more_planes = find_rooms_in_plane(
+            Some(Location { room, x: 0, y: 0 }),
+            left_to_visit,
+            layout,
+            collisions,
+        );
         planes.append(&mut more_planes);
     }
 
     while !left_to_visit.is_empty() {
-        let mut more_planes = find_rooms_in_plane(None, left_to_visit, rules);
+        let mut more_planes = find_rooms_in_plane(None, left_to_visit, layout, collisions);
         planes.append(&mut more_planes);
     }
 
@@ -147,18 +236,9 @@ fn find_rooms_in_plane(
 
 #[cfg(test)]
 mod test {
-    use super::{find_rooms_in_plane, Direction, Location, Rc, Room, Vnum};
-    use crate::model::{Door, Sector};
-
-    fn make_room(vnum: Vnum, exits: &[(Direction, (u32, Door))]) -> Rc<Room> {
-        Rc::new(Room {
-            vnum,
-            name: vnum.to_string(),
-            string_vnum: vnum.to_string(),
-            sector: Sector::Inside,
-            exits: exits.iter().copied().collect(),
-        })
-    }
+    use super::{
+        find_rooms_in_plane, Collision, CollisionFix, Direction, Layout, Location, Rc, Room,
+    };
 
     fn rooms_from_locations(groups: Vec<Vec<Location>>) -> Vec<Vec<Rc<Room>>> {
         groups
@@ -170,53 +250,66 @@ mod test {
     #[test]
     fn find_rooms_in_plane_groups_nsew_connections() {
         let mut rooms = vec![
-            make_room(
+            Rc::new(Room::test(
                 1000,
-                &[
-                    (Direction::North, (1001u32, Door::None)),
-                    (Direction::West, (500u32, Door::None)),
-                ],
-            ),
-            make_room(1001, &[(Direction::South, (1000u32, Door::None))]),
+                &[(Direction::North, 1001), (Direction::West, 500)],
+            )),
+            Rc::new(Room::test(1001, &[(Direction::South, 1000)])),
         ];
 
         let original_rooms = vec![rooms.clone()];
 
-        let planes = find_rooms_in_plane(None, &mut rooms, &[]);
+        let planes = find_rooms_in_plane(None, &mut rooms, &Layout::default(), &mut vec![]);
         let planes = rooms_from_locations(planes);
         assert_eq!(planes, original_rooms);
     }
 
+    #[test]
+    fn find_rooms_in_plane_places_diagonal_connections() {
+        let mut rooms = vec![
+            Rc::new(Room::test(
+                1000,
+                &[(Direction::Northeast, 1001), (Direction::In, 1002)],
+            )),
+            Rc::new(Room::test(1001, &[(Direction::Southwest, 1000)])),
+            Rc::new(Room::test(1002, &[(Direction::Out, 1000)])),
+        ];
+
+        let planes = find_rooms_in_plane(None, &mut rooms, &Layout::default(), &mut vec![]);
+        let positions: Vec<Vec<_>> = planes
+            .iter()
+            .map(|plane| plane.iter().map(|l| (l.room.vnum, l.x, l.y)).collect())
+            .collect();
+        assert_eq!(
+            positions,
+            vec![vec![(1000, 0, 0), (1001, 1, 1)], vec![(1002, 0, 0)]]
+        );
+    }
+
     #[test]
     fn find_rooms_in_plane_separates_updown_connections() {
         let mut rooms = vec![
-            make_room(
+            Rc::new(Room::test(
                 1000,
-                &[
-                    (Direction::North, (1001u32, Door::None)),
-                    (Direction::Up, (1002u32, Door::None)),
-                ],
-            ),
-            make_room(1001, &[(Direction::South, (1000u32, Door::None))]),
-            make_room(1002, &[(Direction::Down, (1000u32, Door::None))]),
+                &[(Direction::North, 1001), (Direction::Up, 1002)],
+            )),
+            Rc::new(Room::test(1001, &[(Direction::South, 1000)])),
+            Rc::new(Room::test(1002, &[(Direction::Down, 1000)])),
         ];
 
-        let planes = find_rooms_in_plane(None, &mut rooms, &[]);
+        let planes = find_rooms_in_plane(None, &mut rooms, &Layout::default(), &mut vec![]);
         let planes = rooms_from_locations(planes);
         assert_eq!(
             planes,
             vec![
                 vec![
-                    make_room(
+                    Rc::new(Room::test(
                         1000,
-                        &[
-                            (Direction::North, (1001u32, Door::None)),
-                            (Direction::Up, (1002u32, Door::None))
-                        ]
-                    ),
-                    make_room(1001, &[(Direction::South, (1000u32, Door::None))]),
+                        &[(Direction::North, 1001), (Direction::Up, 1002)]
+                    )),
+                    Rc::new(Room::test(1001, &[(Direction::South, 1000)])),
                 ],
-                vec![make_room(1002, &[(Direction::Down, (1000u32, Door::None))])],
+                vec![Rc::new(Room::test(1002, &[(Direction::Down, 1000)]))],
             ]
         );
     }
@@ -224,22 +317,65 @@ mod test {
     #[test]
     fn find_rooms_in_plane_includes_orphaned_planes() {
         let mut rooms = vec![
-            make_room(1000, &[(Direction::North, (1001u32, Door::None))]),
-            make_room(1001, &[(Direction::South, (1000u32, Door::None))]),
-            make_room(1002, &[]),
+            Rc::new(Room::test(1000, &[(Direction::North, 1001)])),
+            Rc::new(Room::test(1001, &[(Direction::South, 1000)])),
+            Rc::new(Room::test(1002, &[])),
         ];
 
-        let planes = find_rooms_in_plane(None, &mut rooms, &[]);
+        let planes = find_rooms_in_plane(None, &mut rooms, &Layout::default(), &mut vec![]);
         let planes = rooms_from_locations(planes);
         assert_eq!(
             planes,
             vec![
                 vec![
-                    make_room(1000, &[(Direction::North, (1001u32, Door::None))]),
-                    make_room(1001, &[(Direction::South, (1000u32, Door::None))]),
+                    Rc::new(Room::test(1000, &[(Direction::North, 1001)])),
+                    Rc::new(Room::test(1001, &[(Direction::South, 1000)])),
                 ],
-                vec![make_room(1002, &[])],
+                vec![Rc::new(Room::test(1002, &[]))],
             ]
         )
     }
+
+    #[test]
+    fn find_rooms_in_plane_handles_collisions() {
+        use Direction::*;
+        // Going east, north, west and south comes out next to where it started
+        let rooms = vec![
+            Rc::new(Room::test(1, &[(East, 2)])),
+            Rc::new(Room::test(2, &[(West, 1), (North, 3)])),
+            Rc::new(Room::test(3, &[(South, 2), (West, 4)])),
+            Rc::new(Room::test(4, &[(East, 3), (South, 5)])),
+            Rc::new(Room::test(5, &[(North, 4)])),
+        ];
+        let layout = |collisions| {
+            let layout = Layout {
+                rules: vec![],
+                collisions,
+            };
+            let mut found = vec![];
+            let planes = find_rooms_in_plane(None, &mut rooms.clone(), &layout, &mut found);
+            let positions: Vec<Vec<_>> = planes
+                .iter()
+                .map(|plane| plane.iter().map(|l| (l.room.vnum, l.x, l.y)).collect())
+                .collect();
+            (positions, found)
+        };
+        let square = vec![(1, 0, 0), (2, 1, 0), (3, 1, 1), (4, 0, 1)];
+
+        let (positions, found) = layout(CollisionFix::Report);
+        let mut stacked = square.clone();
+        stacked.push((5, 0, 0));
+        assert_eq!(positions, vec![stacked]);
+        assert_eq!(found, vec![Collision { room: 5, other: 1 }]);
+
+        let (positions, found) = layout(CollisionFix::Split);
+        assert_eq!(positions, vec![square.clone(), vec![(5, 0, 0)]]);
+        assert!(found.is_empty());
+
+        let (positions, found) = layout(CollisionFix::Stretch);
+        let mut stretched = square;
+        stretched.push((5, 0, -1));
+        assert_eq!(positions, vec![stretched]);
+        assert!(found.is_empty());
+    }
 }
@@ -0,0 +1,240 @@
+use crate::model::{Direction, Vnum};
+use crate::parser::Location;
+use fnv::FnvHashMap;
+use std::collections::VecDeque;
+
+/// Where a plane sits among the planes it's joined to by up and down exits
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Floor {
+    /// The lowest numbered plane of the stack
+    pub stack: usize,
+    /// How many floors up from that plane
+    pub level: i32,
+    /// How far the plane's grid is shifted from that plane's, so the rooms at either end of its
+    /// stairs line up
+    pub offset: (i32, i32),
+}
+
+/// An up or down exit that doesn't agree with where the other stairs put its planes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StairMismatch {
+    pub room: Vnum,
+    pub direction: Direction,
+    pub to: Vnum,
+    pub problem: StairProblem,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StairProblem {
+    /// The exit leads to `found` floors up, while the other stairs put it `expected` floors up
+    Level { expected: i32, found: i32 },
+    /// The right floor, but the rooms at either end aren't above each other
+    Misaligned,
+}
+
+/// The floors the planes make up, and the stairs that don't fit them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stacking {
+    /// By plane
+    pub floors: Vec<Floor>,
+    pub mismatches: Vec<StairMismatch>,
+}
+
+/// An up or down exit between two planes
+struct Stairs {
+    from: usize,
+    to: usize,
+    rise: i32,
+    /// Where the second plane's grid is, seen from the first's
+    offset: (i32, i32),
+    room: Vnum,
+    direction: Direction,
+    to_room: Vnum,
+}
+
+/// Works out which floor each plane is on from the up and down exits between them, following
+/// the stairs from the lowest numbered plane of each stack. Stairs that then land somewhere else
+/// are reported, once for each pair of exits.
+pub fn stack_planes(planes: &[Vec<Location>]) -> Stacking {
+    let location_of: FnvHashMap<Vnum, (usize, i32, i32)> = planes
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, plane)| {
+            plane
+                .iter()
+                .map(move |loc| (loc.room.vnum, (idx, loc.x, loc.y)))
+        })
+        .collect();
+
+    let mut all_stairs = vec![];
+    for (from, plane) in planes.iter().enumerate() {
+        for loc in plane {
+            let mut exits: Vec<_> = loc.room.exits.iter().collect();
+            exits.sort_by_key(|(&direction, _)| direction);
+            for (&direction, exit) in exits {
+                let rise = match direction {
+                    Direction::Up => 1,
+                    Direction::Down => -1,
+                    _ => continue,
+                };
+                let (to, x, y) = match location_of.get(&exit.destination) {
+                    Some(&(to, x, y)) if to != from => (to, x, y),
+                    _ => continue,
+                };
+                all_stairs.push(Stairs {
+                    from,
+                    to,
+                    rise,
+                    offset: (loc.x - x, loc.y - y),
+                    room: loc.room.vnum,
+                    direction,
+                    to_room: exit.destination,
+                });
+            }
+        }
+    }
+
+    // Stairs each plane leads along, either way
+    let mut leading = vec![vec![]; planes.len()];
+    for (idx, stairs) in all_stairs.iter().enumerate() {
+        leading[stairs.from].push((idx, false));
+        leading[stairs.to].push((idx, true));
+    }
+
+    let mut placed: Vec<Option<Floor>> = vec![None; planes.len()];
+    for start in 0..planes.len() {
+        if placed[start].is_some() {
+            continue;
+        }
+        placed[start] = Some(Floor {
+            stack: start,
+            level: 0,
+            offset: (0, 0),
+        });
+        let mut queue = VecDeque::from([start]);
+        while let Some(idx) = queue.pop_front() {
+            let floor = placed[idx].unwrap();
+            for &(stairs, backwards) in &leading[idx] {
+                let stairs = &all_stairs[stairs];
+                let (other, rise, (dx, dy)) = if backwards {
+                    (
+                        stairs.from,
+                        -stairs.rise,
+                        (-stairs.offset.0, -stairs.offset.1),
+                    )
+                } else {
+                    (stairs.to, stairs.rise, stairs.offset)
+                };
+                if placed[other].is_none() {
+                    placed[other] = Some(Floor {
+                        stack: start,
+                        level: floor.level + rise,
+                        offset: (floor.offset.0 + dx, floor.offset.1 + dy),
+                    });
+                    queue.push_back(other);
+                }
+            }
+        }
+    }
+    let floors: Vec<_> = placed.into_iter().map(Option::unwrap).collect();
+
+    let mut mismatches = vec![];
+    for stairs in &all_stairs {
+        // A down exit that retraces an up exit was already checked from the room below
+        let up_exit_back = stairs.direction == Direction::Down
+            && all_stairs.iter().any(|other| {
+                other.room == stairs.to_room
+                    && other.to_room == stairs.room
+                    && other.direction == Direction::Up
+            });
+        if up_exit_back {
+            continue;
+        }
+        let (from, to) = (floors[stairs.from], floors[stairs.to]);
+        let problem = if to.level - from.level != stairs.rise {
+            StairProblem::Level {
+                expected: stairs.rise,
+                found: to.level - from.level,
+            }
+        } else if (to.offset.0 - from.offset.0, to.offset.1 - from.offset.1) != stairs.offset {
+            StairProblem::Misaligned
+        } else {
+            continue;
+        };
+        mismatches.push(StairMismatch {
+            room: stairs.room,
+            direction: stairs.direction,
+            to: stairs.to_room,
+            problem,
+        });
+    }
+
+    Stacking { floors, mismatches }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{stack_planes, Floor, StairMismatch, StairProblem};
+    use crate::model::Direction;
+    use crate::parser::Location;
+
+    #[test]
+    fn stacks_floors_and_finds_stairs_that_disagree() {
+        use Direction::*;
+        let planes = vec![
+            // The ground floor, with stairs up from its two ends
+            vec![
+                Location::test(1, (0, 0), &[(East, 2), (Up, 3)]),
+                Location::test(2, (1, 0), &[(West, 1), (Up, 4)]),
+            ],
+            // The first floor, right above the ground floor
+            vec![
+                Location::test(3, (0, 0), &[(Down, 1), (Up, 5)]),
+                Location::test(4, (1, 0), &[(Down, 2)]),
+            ],
+            // Down to the ground floor, but up from the first floor as well
+            vec![Location::test(5, (2, 2), &[(Down, 2)])],
+            // A plane on its own
+            vec![Location::test(6, (0, 0), &[])],
+        ];
+
+        let stacking = stack_planes(&planes);
+        let floor = |stack, level, offset| Floor {
+            stack,
+            level,
+            offset,
+        };
+        assert_eq!(
+            stacking.floors,
+            vec![
+                floor(0, 0, (0, 0)),
+                floor(0, 1, (0, 0)),
+                floor(0, 1, (-1, -2)),
+                floor(3, 0, (0, 0)),
+            ]
+        );
+        assert_eq!(
+            stacking.mismatches,
+            vec![StairMismatch {
+                room: 3,
+                direction: Up,
+                to: 5,
+                problem: StairProblem::Level {
+                    expected: 1,
+                    found: 0
+                },
+            }]
+        );
+
+        // Moving room 4 over a square leaves it on the right floor, but not above room 2
+        let mut planes = planes;
+        planes[1][1] = Location::test(4, (2, 0), &[(Down, 2)]);
+        planes[2] = vec![];
+        let mismatches: Vec<_> = stack_planes(&planes)
+            .mismatches
+            .into_iter()
+            .map(|mismatch| (mismatch.room, mismatch.problem))
+            .collect();
+        assert_eq!(mismatches, vec![(2, StairProblem::Misaligned)]);
+    }
+}